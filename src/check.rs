@@ -0,0 +1,118 @@
+use std::{
+    fmt::Display,
+    fs::read_dir,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use color_print::ceprintln;
+
+use crate::{
+    config::Config,
+    link_checker::check_links_root,
+    locator::Locator,
+    reader::{file_order_index, is_asset, is_asset_dir, is_hidden, sibling_collisions},
+    render::read_files,
+};
+
+/// something wrong with the content, found without building
+pub struct Problem {
+    /// file or url the problem is about
+    pub location: String,
+    pub message: String,
+}
+
+impl Problem {
+    fn new(path: &Path, message: String) -> Self {
+        Problem {
+            location: path.display().to_string(),
+            message,
+        }
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// validates the content directory and its links, without writing anything
+///
+/// links are only checked once the structure is valid, as reading it would report the same
+/// problems again
+pub fn check_content() -> Result<Vec<Problem>> {
+    let mut problems = Vec::new();
+    check_dir(&Config::get().content, &Locator::new(""), &mut problems)?;
+
+    if problems.is_empty()
+        && let Err(err) = read_files()
+    {
+        problems.push(Problem {
+            location: Config::get().content.display().to_string(),
+            message: format!("Failed to read content: {err:#}"),
+        });
+    }
+
+    for problem in &problems {
+        ceprintln!("<red>{problem}</red>");
+    }
+
+    if problems.is_empty() {
+        // dead links are printed while they are checked
+        for dead_link in check_links_root()? {
+            problems.push(Problem {
+                location: dead_link.source.clone(),
+                message: dead_link.to_string(),
+            });
+        }
+    }
+
+    Ok(problems)
+}
+
+/// checks the structure of a section directory and all its subsections
+fn check_dir(dir: &Path, loc: &Locator, problems: &mut Vec<Problem>) -> Result<()> {
+    if !dir.join("index.md").is_file() {
+        problems.push(Problem::new(dir, String::from("Section has no index.md")));
+    }
+
+    let mut entries = read_dir(dir)
+        .with_context(|| format!("Failed to read dir with path {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<PathBuf>>>()?;
+    entries.sort();
+
+    let mut siblings = Vec::new();
+    for path in entries {
+        if path.ends_with("index.md") {
+            siblings.push(path);
+            continue;
+        }
+
+        // hidden files like .DS_Store or .gitkeep are not part of the site
+        if is_hidden(&path) || (is_asset(&path) && path.is_file()) || is_asset_dir(&path) {
+            continue;
+        }
+
+        if file_order_index(&path).is_err() {
+            problems.push(Problem::new(
+                &path,
+                String::from("Name does not start with an order index like '1_'"),
+            ));
+            continue;
+        }
+
+        if path.is_dir() {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            check_dir(&path, &loc.join(&Locator::new(&name)), problems)?;
+        }
+        siblings.push(path);
+    }
+
+    for collision in sibling_collisions(loc, siblings) {
+        problems.push(Problem::new(collision.second(), collision.to_string()));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use std::{net::IpAddr, path::PathBuf};
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::config::Overrides;
+
+/// Static site generator for markdown wikis
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Config file to use instead of ligny.toml
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Directory with the markdown content
+    #[arg(long, global = true)]
+    pub content: Option<PathBuf>,
+
+    /// Directory with static files like css and scripts
+    #[arg(long = "static", global = true)]
+    pub static_dir: Option<PathBuf>,
+
+    /// Directory with templates that replace the built-in ones
+    #[arg(long, global = true)]
+    pub templates: Option<PathBuf>,
+
+    /// Defaults to build
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Render all pages into the public directory
+    Build(BuildArgs),
+    /// Serve the site, rerendering pages when the content changes
+    Serve(ServeArgs),
+    /// Create the default static files and templates
+    Init(InitArgs),
+    /// Validate the content and links without writing anything
+    Check,
+}
+
+#[derive(Args, Default)]
+pub struct BuildArgs {
+    /// Directory to write the site to
+    #[arg(long)]
+    pub out: Option<String>,
+
+    /// Fail when there are dead links
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Include pages marked as draft
+    #[arg(long)]
+    pub drafts: bool,
+
+    /// Empty the output directory before building
+    #[arg(long)]
+    pub clean: bool,
+}
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Port to listen on
+    #[arg(long)]
+    pub port: Option<u16>,
+
+    /// Address to listen on
+    #[arg(long)]
+    pub address: Option<IpAddr>,
+
+    /// Open the site in the default browser
+    #[arg(long)]
+    pub open: bool,
+
+    /// Do not reload the browser when files change
+    #[arg(long)]
+    pub no_reload: bool,
+}
+
+#[derive(Args)]
+pub struct InitArgs {
+    /// Overwrite existing static files and templates
+    #[arg(long)]
+    pub force: bool,
+
+    /// Directory to initialize
+    #[arg(default_value = ".")]
+    pub dir: PathBuf,
+}
+
+impl Cli {
+    /// the config settings given on the command line
+    pub fn overrides(&self) -> Overrides {
+        let mut overrides = Overrides {
+            config: self.config.clone(),
+            content: self.content.clone(),
+            static_dir: self.static_dir.clone(),
+            templates: self.templates.clone(),
+            ..Default::default()
+        };
+
+        match &self.command {
+            Some(Command::Build(args)) => {
+                overrides.public = args.out.clone();
+                overrides.strict = args.strict.then_some(true);
+                overrides.drafts = Some(args.drafts);
+            }
+            Some(Command::Serve(args)) => {
+                overrides.port = args.port;
+                overrides.address = args.address;
+                overrides.drafts = Some(true);
+                overrides.live_reload = Some(!args.no_reload);
+            }
+            Some(Command::Init(_) | Command::Check) | None => (),
+        }
+
+        overrides
+    }
+}
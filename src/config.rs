@@ -1,41 +1,221 @@
-use std::{env, net::IpAddr, path::PathBuf, sync::OnceLock};
+use std::{
+    env,
+    fmt::Display,
+    fs::read_to_string,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+};
 
+use anyhow::{Context, Result, anyhow, bail};
 use dotenvy::dotenv;
+use serde::{Deserialize, Serialize};
 
 static CONFIG: OnceLock<Config> = OnceLock::new();
 
+static CONFIG_FILE: &str = "ligny.toml";
+
 pub struct Config {
     pub public: String,
     pub content: PathBuf,
     pub static_dir: PathBuf,
+    /// templates in this directory replace the built-in ones with the same name
+    pub templates: PathBuf,
     pub index_name: String,
     pub port: u16,
     pub address: IpAddr,
+    /// fail the build when there are dead links
+    pub strict: bool,
+    /// where to write the JSON report of dead links, if anywhere
+    pub link_report: Option<PathBuf>,
+    /// render pages marked as draft
+    pub drafts: bool,
+    /// let the browser reload pages when files change
+    pub live_reload: bool,
+    pub site: SiteConfig,
+    pub search: SearchConfig,
 }
 
-impl Config {
-    pub fn initialize() {
-        assert!(CONFIG.get().is_none());
+/// settings describing the site itself, available in every template
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct SiteConfig {
+    pub title: String,
+    pub description: Option<String>,
+    /// absolute url the site is hosted on, used for canonical links
+    pub base_url: String,
+    pub language: String,
+    /// url of an image shown in the header, e.g. a file in the static directory
+    pub logo: Option<String>,
+    pub footer: Option<String>,
+    /// text of the link to the root page in the header, defaults to the title
+    pub header_link_text: Option<String>,
+    /// extra links shown in the header
+    pub nav: Vec<NavLink>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NavLink {
+    pub title: String,
+    pub url: String,
+}
+
+/// settings for the search index
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchConfig {
+    /// urls of pages left out of search, together with everything below them
+    pub exclude: Vec<String>,
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        SiteConfig {
+            title: String::from("ligny"),
+            description: None,
+            base_url: String::new(),
+            language: String::from("en"),
+            logo: None,
+            footer: None,
+            header_link_text: None,
+            nav: Vec::new(),
+        }
+    }
+}
+
+/// settings given on the command line, these take precedence over the environment and config file
+#[derive(Default)]
+pub struct Overrides {
+    pub config: Option<PathBuf>,
+    pub public: Option<String>,
+    pub content: Option<PathBuf>,
+    pub static_dir: Option<PathBuf>,
+    pub templates: Option<PathBuf>,
+    pub port: Option<u16>,
+    pub address: Option<IpAddr>,
+    pub strict: Option<bool>,
+    pub drafts: Option<bool>,
+    pub live_reload: Option<bool>,
+}
+
+/// the contents of `ligny.toml`, every setting is optional
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    public: Option<String>,
+    content: Option<PathBuf>,
+    #[serde(rename = "static")]
+    static_dir: Option<PathBuf>,
+    templates: Option<PathBuf>,
+    index: Option<String>,
+    port: Option<u16>,
+    address: Option<IpAddr>,
+    strict: Option<bool>,
+    link_report: Option<PathBuf>,
+    site: SiteConfig,
+    search: SearchConfig,
+}
 
-        Config::get();
+impl Config {
+    /// load the config, in order of precedence, from the command line, environment variables
+    /// (or `.env`), `ligny.toml` and the defaults
+    pub fn initialize(overrides: Overrides) -> Result<()> {
+        let config = Config::load(overrides)?;
+        CONFIG
+            .set(config)
+            .map_err(|_| anyhow!("Config is already initialized"))
     }
 
     pub fn get() -> &'static Config {
-        CONFIG.get_or_init(|| {
-            dotenv().ok();
-            Config {
-                public: env::var("PUBLIC").unwrap_or("public".into()),
-                content: PathBuf::from(env::var("CONTENT").unwrap_or("0_content".into())),
-                static_dir: PathBuf::from(env::var("STATIC").unwrap_or("static".into())),
-                index_name: env::var("INDEX").unwrap_or("index.json".into()),
-                port: env::var("PORT")
-                    .map(|v| v.parse::<u16>().expect("PORT is invalid"))
-                    .unwrap_or(8000),
-                address: env::var("ADDRESS")
-                    .unwrap_or(String::from("127.0.0.1"))
-                    .parse()
-                    .expect("ADDRESS is invalid"),
-            }
+        CONFIG.get().expect("Config is not initialized")
+    }
+
+    fn load(overrides: Overrides) -> Result<Config> {
+        dotenv().ok();
+        let file = ConfigFile::read(overrides.config.as_deref())?;
+
+        Ok(Config {
+            public: overrides
+                .public
+                .or(env_var("PUBLIC")?)
+                .or(file.public)
+                .unwrap_or("public".into()),
+            content: overrides
+                .content
+                .or(env_var("CONTENT")?)
+                .or(file.content)
+                .unwrap_or("0_content".into()),
+            static_dir: overrides
+                .static_dir
+                .or(env_var("STATIC")?)
+                .or(file.static_dir)
+                .unwrap_or("static".into()),
+            templates: overrides
+                .templates
+                .or(env_var("TEMPLATES")?)
+                .or(file.templates)
+                .unwrap_or("templates".into()),
+            index_name: env_var("INDEX")?
+                .or(file.index)
+                .unwrap_or("index.json".into()),
+            port: overrides
+                .port
+                .or(env_var("PORT")?)
+                .or(file.port)
+                .unwrap_or(8000),
+            address: overrides
+                .address
+                .or(env_var("ADDRESS")?)
+                .or(file.address)
+                .unwrap_or(IpAddr::from([127, 0, 0, 1])),
+            strict: overrides
+                .strict
+                .or(env_var("STRICT")?)
+                .or(file.strict)
+                .unwrap_or(false),
+            link_report: env_var("LINK_REPORT")?.or(file.link_report),
+            drafts: overrides.drafts.unwrap_or(false),
+            live_reload: overrides.live_reload.unwrap_or(false),
+            site: file.site,
+            search: file.search,
         })
     }
 }
+
+impl ConfigFile {
+    /// reads the given config file, or `ligny.toml` if it exists
+    fn read(path: Option<&Path>) -> Result<ConfigFile> {
+        let path = match path {
+            Some(path) => path,
+            None if Path::new(CONFIG_FILE).exists() => Path::new(CONFIG_FILE),
+            None => return Ok(ConfigFile::default()),
+        };
+
+        if !path.is_file() {
+            bail!("Config file '{}' does not exist", path.display());
+        }
+
+        let content = read_to_string(path)
+            .with_context(|| format!("Failed to read config file '{}'", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid config file '{}'", path.display()))
+    }
+}
+
+/// reads and parses an environment variable, `None` if it is not set
+fn env_var<T>(name: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    env::var(name)
+        .ok()
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|err| anyhow!("{name} is invalid: '{value}' ({err})"))
+        })
+        .transpose()
+}
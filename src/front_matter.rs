@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, MapAccess, Visitor, value::MapAccessDeserializer},
+};
+
+/// metadata at the top of a markdown file, fenced by `---` (YAML) or `+++` (TOML)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FrontMatter {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub tags: Vec<String>,
+    pub draft: bool,
+    /// name of the template in `layouts/` to render the page with
+    pub layout: Option<String>,
+    /// on a section's index page, the layout of every page below it without its own layout
+    pub default_layout: Option<String>,
+    /// `search: false` is short for `search: { exclude: true }`
+    #[serde(deserialize_with = "deserialize_search")]
+    pub search: SearchMeta,
+    /// any keys not known to ligny, kept so templates can use them
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// how a page is found by search
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchMeta {
+    /// leave the page out of the search index
+    pub exclude: bool,
+    /// scores of the page are multiplied by this
+    pub weight: f64,
+    /// other words the page should be found by, like abbreviations
+    pub keywords: Vec<String>,
+}
+
+impl Default for SearchMeta {
+    fn default() -> Self {
+        SearchMeta {
+            exclude: false,
+            weight: 1.0,
+            keywords: Vec::new(),
+        }
+    }
+}
+
+/// reads `search` as a boolean or the full settings, checking the weight
+fn deserialize_search<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SearchMeta, D::Error> {
+    struct SearchVisitor;
+
+    impl<'de> Visitor<'de> for SearchVisitor {
+        type Value = SearchMeta;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a boolean or a map with exclude, weight and keywords")
+        }
+
+        fn visit_bool<E: de::Error>(self, include: bool) -> Result<SearchMeta, E> {
+            Ok(SearchMeta {
+                exclude: !include,
+                ..SearchMeta::default()
+            })
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<SearchMeta, A::Error> {
+            SearchMeta::deserialize(MapAccessDeserializer::new(map))
+        }
+    }
+
+    let search = deserializer.deserialize_any(SearchVisitor)?;
+    if !search.weight.is_finite() || search.weight <= 0.0 {
+        return Err(de::Error::custom(format!(
+            "search weight must be a number above 0, got {}",
+            search.weight
+        )));
+    }
+
+    Ok(search)
+}
+
+enum Format {
+    Yaml,
+    Toml,
+}
+
+/// splits the front matter from the markdown body
+///
+/// returns the default (empty) front matter if the file does not start with a fence
+pub fn split_front_matter(content: &str) -> Result<(FrontMatter, &str)> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    for (fence, format) in [("---", Format::Yaml), ("+++", Format::Toml)] {
+        let Some((raw, body)) = fenced_block(content, fence) else {
+            continue;
+        };
+
+        if raw.trim().is_empty() {
+            return Ok((FrontMatter::default(), body));
+        }
+
+        let front_matter = match format {
+            Format::Yaml => serde_yaml::from_str(raw).context("Invalid YAML front matter")?,
+            Format::Toml => {
+                let value = toml::from_str(raw).context("Invalid TOML front matter")?;
+                serde_json::from_value(toml_to_json(value)).context("Invalid TOML front matter")?
+            }
+        };
+        return Ok((front_matter, body));
+    }
+
+    Ok((FrontMatter::default(), content))
+}
+
+/// converts TOML to JSON, so unknown keys end up in `extra` as plain values
+///
+/// datetimes become strings, deserializing them directly keeps the private wrapper of the toml
+/// crate
+fn toml_to_json(value: toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(s) => serde_json::Value::String(s),
+        toml::Value::Integer(i) => i.into(),
+        // JSON has no NaN or infinity, as strings they are still rejected where a number is needed
+        toml::Value::Float(f) => serde_json::Number::from_f64(f)
+            .map_or_else(|| serde_json::Value::String(f.to_string()), Into::into),
+        toml::Value::Boolean(b) => b.into(),
+        toml::Value::Datetime(datetime) => serde_json::Value::String(datetime.to_string()),
+        toml::Value::Array(array) => array.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => table
+            .into_iter()
+            .map(|(key, value)| (key, toml_to_json(value)))
+            .collect(),
+    }
+}
+
+/// returns the text between an opening fence on the first line and the next closing fence,
+/// together with everything after the closing fence
+fn fenced_block<'a>(content: &'a str, fence: &str) -> Option<(&'a str, &'a str)> {
+    let mut lines = content.split_inclusive('\n');
+    if lines.next()?.trim_end() != fence {
+        return None;
+    }
+
+    let start = content.find('\n')? + 1;
+    let mut offset = start;
+    for line in lines {
+        if line.trim_end() == fence {
+            return Some((&content[start..offset], &content[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{locator::Locator, reader::parse_page};
+
+    fn front_matter(content: &str) -> FrontMatter {
+        split_front_matter(content).unwrap().0
+    }
+
+    #[test]
+    fn yaml_front_matter() {
+        let content = "---\ntitle: Yaml\ndate: 2024-01-01\ncustom: [1, 2]\n---\n# Body\n";
+        let (meta, body) = split_front_matter(content).unwrap();
+
+        assert_eq!(meta.title.as_deref(), Some("Yaml"));
+        assert_eq!(meta.extra["date"], serde_json::json!("2024-01-01"));
+        assert_eq!(meta.extra["custom"], serde_json::json!([1, 2]));
+        assert_eq!(body, "# Body\n");
+    }
+
+    #[test]
+    fn toml_front_matter() {
+        let content = "+++\ntitle = \"Toml\"\ndate = 2024-01-01\n[custom]\nkey = true\n+++\nbody";
+        let meta = front_matter(content);
+
+        assert_eq!(meta.title.as_deref(), Some("Toml"));
+        assert_eq!(meta.extra["date"], serde_json::json!("2024-01-01"));
+        assert_eq!(meta.extra["custom"], serde_json::json!({ "key": true }));
+    }
+
+    #[test]
+    fn without_front_matter() {
+        let (meta, body) = split_front_matter("# Title\n---\n").unwrap();
+        assert_eq!(meta, FrontMatter::default());
+        assert_eq!(body, "# Title\n---\n");
+    }
+
+    #[test]
+    fn search_settings() {
+        assert!(front_matter("---\nsearch: false\n---\n").search.exclude);
+        assert!(!front_matter("---\nsearch: true\n---\n").search.exclude);
+
+        let meta = front_matter("+++\n[search]\nweight = 2.5\nkeywords = [\"k8s\"]\n+++\n");
+        assert_eq!(meta.search.weight, 2.5);
+        assert_eq!(meta.search.keywords, ["k8s"]);
+
+        for weight in ["0", "-1", ".nan", ".inf"] {
+            let content = format!("---\nsearch:\n  weight: {weight}\n---\n");
+            assert!(split_front_matter(&content).is_err(), "weight {weight}");
+        }
+        assert!(split_front_matter("+++\n[search]\nweight = nan\n+++\n").is_err());
+        assert!(split_front_matter("---\nsearch:\n  wieght: 2\n---\n").is_err());
+    }
+
+    #[test]
+    fn title_takes_precedence_over_filename() {
+        let loc = Locator::new("1_page.md");
+        let titled = parse_page("---\ntitle: Custom\n---\ntext", "page".into(), loc.clone());
+        let untitled = parse_page("+++\nauthor = \"me\"\n+++\ntext", "page".into(), loc);
+
+        assert_eq!(titled.unwrap().title, "Custom");
+        assert_eq!(untitled.unwrap().title, "page");
+    }
+}
@@ -1,43 +1,56 @@
 use std::{
-    fs::{File, create_dir, exists},
+    fs::{File, create_dir, create_dir_all, exists},
     io::Write,
-    path::PathBuf,
+    path::Path,
 };
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
 use include_dir::{Dir, include_dir};
 
+use crate::templates::TEMPLATE_DIR;
+
 static STATIC_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/static/");
-static TEMPLATE_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/");
 
-pub fn init_files() -> Result<()> {
+/// create the default static files and templates in `dir`
+///
+/// existing files are only overwritten with `force`
+pub fn init_files(dir: &Path, force: bool) -> Result<()> {
+    let static_dir = dir.join("static/");
+    let template_dir = dir.join("templates/");
+
     // if either exists, or an error occured, exit
-    if exists("static/").unwrap_or(true) || exists("templates/").unwrap_or(true) {
-        bail!("'static/' or 'templates/' already exists, not creating either")
+    if !force && (exists(&static_dir).unwrap_or(true) || exists(&template_dir).unwrap_or(true)) {
+        bail!(
+            "'{}' or '{}' already exists, not creating either. Use --force to overwrite.",
+            static_dir.display(),
+            template_dir.display()
+        )
     }
 
-    init_default_static()?;
-    init_default_templates()?;
+    create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory '{}'", dir.display()))?;
+    init_default_static(&static_dir)?;
+    init_default_templates(&template_dir)?;
 
     Ok(())
 }
 
-pub fn init_default_static() -> Result<()> {
-    create_dir("static/").or(anyhow::Ok(()))?;
+pub fn init_default_static(static_dir: &Path) -> Result<()> {
+    create_dir(static_dir).or(anyhow::Ok(()))?;
     for file in STATIC_DIR.files() {
-        let path = PathBuf::from("static/").join(file.path());
+        let path = static_dir.join(file.path());
         File::create(path)?.write_all(file.contents())?;
     }
 
     Ok(())
 }
 
-pub fn init_default_templates() -> Result<()> {
-    create_dir("templates/").or(anyhow::Ok(()))?;
-    for file in TEMPLATE_DIR.files() {
-        let path = PathBuf::from("templates/").join(file.path());
-        File::create(path)?.write_all(file.contents())?;
-    }
+/// writes the built-in templates, including the layouts and partials in subdirectories
+pub fn init_default_templates(template_dir: &Path) -> Result<()> {
+    create_dir(template_dir).or(anyhow::Ok(()))?;
+    TEMPLATE_DIR
+        .extract(template_dir)
+        .with_context(|| format!("Failed to write templates to '{}'", template_dir.display()))?;
 
     Ok(())
 }
@@ -1,39 +1,179 @@
-use anyhow::Result;
-use std::collections::HashMap;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::{fmt::Display, fs::File, io::Write, ops::Deref, path::Path};
 
 use crate::{
-    CONTEXT,
-    locator::Locator,
-    reader::{Page, Section, ThreadNodeType},
+    CONTEXT, Static,
+    reader::{Page, Section, ThreadNode},
     render::get_root,
 };
 use color_print::ceprintln;
 
-pub fn check_links(page: &Page, reads: &HashMap<Locator, ThreadNodeType>) {
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadLinkKind {
+    /// the target page does not exist
+    Page,
+    /// the target page exists, but has no heading with the fragment as id
+    Anchor,
+    /// the referenced file is not in the content directory
+    Asset,
+    /// a published page links to a draft, which will not be published
+    Draft,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DeadLink {
+    pub kind: DeadLinkKind,
+    pub source: String,
+    pub source_title: String,
+    pub target: String,
+}
+
+impl Display for DeadLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            DeadLinkKind::Page => write!(
+                f,
+                "Dead link in page {}, pointing to non-existing {}",
+                self.source_title, self.target
+            ),
+            DeadLinkKind::Anchor => write!(
+                f,
+                "Dead anchor in page {}, pointing to non-existing heading {}",
+                self.source_title, self.target
+            ),
+            DeadLinkKind::Asset => write!(
+                f,
+                "Missing asset in page {}, pointing to non-existing file {}",
+                self.source_title, self.target
+            ),
+            DeadLinkKind::Draft => write!(
+                f,
+                "Link in page {} points to draft {}",
+                self.source_title, self.target
+            ),
+        }
+    }
+}
+
+impl DeadLink {
+    fn new(kind: DeadLinkKind, page: &Page, target: String) -> Self {
+        DeadLink {
+            kind,
+            source: page.loc.url(),
+            source_title: page.title.clone(),
+            target,
+        }
+    }
+}
+
+pub fn check_links(page: &Page, context: &Static) -> Vec<DeadLink> {
+    let mut dead_links = Vec::new();
+
+    // hidden drafts are not published, so neither are their links
+    if page.is_hidden() {
+        return dead_links;
+    }
+
     for link in &page.links {
-        if !reads.contains_key(link) {
-            ceprintln!(
-                "<yellow>Dead link in page {}, pointing to non-existing {link}</yellow>",
-                page.title
-            );
+        // same page links are checked without locking, the page might be locked by the caller
+        if link.loc == page.loc {
+            dead_links.extend(check_fragment(page, page, &link.fragment));
+            continue;
+        }
+
+        match context.reads.get(&link.loc) {
+            Some(node) => {
+                let node = node.lock().unwrap();
+                let target = match node.deref() {
+                    ThreadNode::Section(section) => &section.body,
+                    ThreadNode::Page(target) => target,
+                };
+
+                if target.draft && !page.draft {
+                    dead_links.push(DeadLink::new(DeadLinkKind::Draft, page, link.loc.url()));
+                } else {
+                    dead_links.extend(check_fragment(page, target, &link.fragment));
+                }
+            }
+            None => dead_links.push(DeadLink::new(DeadLinkKind::Page, page, link.loc.url())),
+        }
+    }
+
+    for asset in &page.assets {
+        if !context.assets.contains_key(asset) {
+            dead_links.push(DeadLink::new(DeadLinkKind::Asset, page, asset.url()));
         }
     }
+
+    for dead_link in &dead_links {
+        ceprintln!("<yellow>{dead_link}</yellow>");
+    }
+
+    dead_links
+}
+
+/// checks that the fragment matches the id of a heading on the target page
+fn check_fragment(page: &Page, target: &Page, fragment: &Option<String>) -> Option<DeadLink> {
+    let fragment = fragment.as_ref()?;
+
+    if target.toc.iter().any(|heading| &heading.id == fragment) {
+        None
+    } else {
+        Some(DeadLink::new(
+            DeadLinkKind::Anchor,
+            page,
+            format!("{}#{fragment}", target.loc.url()),
+        ))
+    }
 }
 
-pub fn check_links_root() -> Result<()> {
+pub fn check_links_root() -> Result<Vec<DeadLink>> {
     let context = CONTEXT.lock().unwrap();
     let root = get_root(&context.reads)?;
-    check_links_section(&root, &context.reads);
 
-    Ok(())
+    Ok(check_links_section(&root, &context))
 }
 
-pub fn check_links_section(section: &Section, reads: &HashMap<Locator, ThreadNodeType>) {
-    check_links(&section.body, reads);
+pub fn check_links_section(section: &Section, context: &Static) -> Vec<DeadLink> {
+    let mut dead_links = check_links(&section.body, context);
     for child in &section.children {
         match child {
-            crate::reader::Node::Section(section) => check_links_section(section, reads),
-            crate::reader::Node::Page(page) => check_links(page, reads),
+            crate::reader::Node::Section(section) => {
+                dead_links.append(&mut check_links_section(section, context))
+            }
+            crate::reader::Node::Page(page) => dead_links.append(&mut check_links(page, context)),
         }
     }
+    dead_links
+}
+
+/// write the dead links as JSON, so they can be processed by other tools
+pub fn write_report(dead_links: &[DeadLink], path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(dead_links)?;
+    let mut file = File::create(path)
+        .with_context(|| format!("Failed to create file: '{}'", path.display()))?;
+
+    file.write_all(json.as_bytes())
+        .with_context(|| format!("Failed to write json to file: '{}'", path.display()))?;
+
+    Ok(())
+}
+
+/// a short overview of all dead links, used when failing a strict build
+pub fn summary(dead_links: &[DeadLink]) -> String {
+    let mut pages: Vec<&str> = dead_links.iter().map(|d| d.source.as_str()).collect();
+    pages.sort();
+    pages.dedup();
+
+    let mut summary = format!(
+        "Found {} dead link(s) in {} page(s):",
+        dead_links.len(),
+        pages.len()
+    );
+    for dead_link in dead_links {
+        summary.push_str(&format!("\n  {} -> {}", dead_link.source, dead_link.target));
+    }
+    summary
 }
@@ -3,8 +3,13 @@ use std::hash::Hash;
 use std::path::Path;
 use std::{cmp::Eq, path::PathBuf};
 
+use serde::{Serialize, Serializer};
+
 use crate::config::Config;
 
+/// a file or directory name ending in this, before the extension, marks a draft
+pub static DRAFT_SUFFIX: &str = ".draft";
+
 #[derive(Clone, Debug)]
 pub struct Locator {
     components: Vec<String>,
@@ -20,6 +25,7 @@ impl Locator {
             .filter(|c| !c.is_empty())
             .map(|c| c.split_once('_').map(|(_, e)| String::from(e)).unwrap_or(c))
             .map(|e| e.replace(".md", ""))
+            .map(strip_draft)
             .collect::<Vec<String>>();
 
         Locator { components }
@@ -34,6 +40,21 @@ impl Locator {
         Locator { components }
     }
 
+    /// a relative directory of an asset, only section directories have their order index and
+    /// draft suffix stripped, other directories like `img_dir` keep their name
+    pub fn from_asset_dir(dir: &str) -> Self {
+        let components = dir
+            .split("/")
+            .filter(|c| !c.is_empty())
+            .map(|c| match c.split_once('_') {
+                Some((index, name)) if index.parse::<u32>().is_ok() => strip_draft(name.to_owned()),
+                _ => c.to_owned(),
+            })
+            .collect();
+
+        Locator { components }
+    }
+
     pub fn from_content_path(path: &Path) -> Result<Self> {
         let abs = path.canonicalize().unwrap_or(path.into());
         let stripped = abs.strip_prefix(Config::get().content.canonicalize()?)?;
@@ -56,7 +77,7 @@ impl Locator {
                         },
                         |(_, e)| Ok(e),
                     )
-                    .map(|e| e.replace(".md", ""))
+                    .map(|e| strip_draft(e.replace(".md", "")))
             })
             .collect::<Result<Vec<String>>>()?;
         Ok(Locator { components })
@@ -84,6 +105,13 @@ impl Locator {
         locator_new
     }
 
+    /// whether this locator is `other` or below it
+    pub fn starts_with(&self, other: &Locator) -> bool {
+        let own = self.url();
+        let other = other.url();
+        own == other || other == "/" || own.starts_with(&format!("{other}/"))
+    }
+
     pub fn url(&self) -> String {
         let mut url = self.join_components();
         url.insert(0, '/');
@@ -100,15 +128,12 @@ impl Locator {
     }
 
     pub fn public_path(&self) -> PathBuf {
-        let path = Locator::new(&Config::get().public)
-            .join(self)
-            .join(&Locator::new("index.html"));
-        PathBuf::from(path.join_components())
+        self.public_dir().join("index.html")
     }
 
+    /// the public dir is used as it is given, it is not a content path with order indices
     pub fn public_dir(&self) -> PathBuf {
-        let path = Locator::new(&Config::get().public).join(self);
-        PathBuf::from(path.join_components())
+        PathBuf::from(&Config::get().public).join(self.join_components())
     }
 
     pub fn static_path(&self) -> PathBuf {
@@ -116,12 +141,27 @@ impl Locator {
     }
 }
 
+/// drafts have the same url as when they are published
+fn strip_draft(component: String) -> String {
+    match component.strip_suffix(DRAFT_SUFFIX) {
+        Some(stripped) => String::from(stripped),
+        None => component,
+    }
+}
+
 impl Display for Locator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.url())
     }
 }
 
+/// locators are serialized as their url, so templates can link to pages directly
+impl Serialize for Locator {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.url())
+    }
+}
+
 impl Hash for Locator {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         let url = self.url();
@@ -1,4 +1,7 @@
+mod check;
+mod cli;
 mod config;
+mod front_matter;
 mod init;
 mod link_checker;
 mod locator;
@@ -11,56 +14,80 @@ mod watcher;
 
 use std::{
     collections::HashMap,
-    env,
+    path::PathBuf,
     sync::{LazyLock, Mutex},
 };
 
 use anyhow::{Ok, Result, bail};
+use check::check_content;
+use clap::Parser;
+use cli::{BuildArgs, Cli, Command};
 use config::Config;
-use link_checker::check_links_root;
+use link_checker::{check_links_root, summary, write_report};
 use locator::Locator;
 use reader::ThreadNodeType;
 use render::{read_files, write_pages_to_files};
+use search::SearchIndex;
 use serve::serve;
 
 use crate::init::init_files;
 
-static BUILD_COMMAND: &str = "build";
-
+#[derive(Default)]
 pub struct Static {
     reads: HashMap<Locator, ThreadNodeType>,
+    /// files next to the pages in the content directory, by their url
+    assets: HashMap<Locator, PathBuf>,
+    /// built when first searched, cleared when the content changes
+    search: Option<SearchIndex>,
 }
 
 pub static CONTEXT: LazyLock<Mutex<Static>> = LazyLock::new(|| {
     Mutex::new(Static {
         reads: HashMap::new(),
+        assets: HashMap::new(),
+        search: None,
     })
 });
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    Config::initialize();
+    let cli = Cli::parse();
+    Config::initialize(cli.overrides())?;
 
-    let args: Vec<String> = env::args().collect();
+    match cli.command.unwrap_or(Command::Build(BuildArgs::default())) {
+        Command::Build(args) => {
+            read_files()?;
+            let dead_links = check_links_root()?;
+            let failed = Config::get().strict && !dead_links.is_empty();
+            if !failed {
+                write_pages_to_files(args.clean)?;
+            }
 
-    let command = args.get(1).map(|a| a.as_str()).unwrap_or(BUILD_COMMAND);
+            // written after the build, so it can be in the public dir without being removed
+            if let Some(path) = &Config::get().link_report {
+                write_report(&dead_links, path)?;
+            }
 
-    match command {
-        "build" => {
-            read_files()?;
-            check_links_root()?;
-            write_pages_to_files()
+            if failed {
+                bail!("{}", summary(&dead_links));
+            }
+            Ok(())
         }
-        "serve" => {
+        Command::Serve(args) => {
             read_files()?;
             check_links_root()?;
-            serve().await
+            serve(args.open).await
+        }
+        Command::Init(args) => init_files(&args.dir, args.force),
+        Command::Check => {
+            let problems = check_content()?;
+            if !problems.is_empty() {
+                bail!("Found {} problem(s)", problems.len());
+            }
+
+            println!("No problems found");
+            Ok(())
         }
-        "init" => init_files(),
-        _ => bail!(
-            "Command '{}' not found. Use 'init', 'build' or 'serve'.",
-            command
-        ),
     }?;
 
     Ok(())
@@ -1,4 +1,6 @@
 use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
     fs::{DirEntry, read_dir, read_to_string},
     ops::Deref,
     path::{Path, PathBuf},
@@ -9,14 +11,19 @@ use anyhow::{Context, Result, anyhow, bail};
 use color_print::ceprintln;
 use markdown_ppp::{
     self,
-    ast::{Block, Inline},
+    ast::{Block, Document, HeadingKind, Inline, SetextHeading},
     html_printer::{config::Config, render_html},
     parser::parse_markdown,
 };
+use serde::Serialize;
 
 use std::sync::Arc;
 
-use crate::{Static, locator::Locator};
+use crate::{
+    Static,
+    front_matter::{FrontMatter, split_front_matter},
+    locator::{DRAFT_SUFFIX, Locator},
+};
 
 pub type ThreadNodeType = Arc<Mutex<ThreadNode>>;
 
@@ -77,6 +84,16 @@ impl From<&ThreadNode> for Node {
     }
 }
 
+impl Node {
+    /// the page itself, or the index page of a section
+    pub fn page(&self) -> &Page {
+        match self {
+            Node::Section(section) => &section.body,
+            Node::Page(page) => page,
+        }
+    }
+}
+
 impl ThreadNode {
     pub fn get_section_mut(&mut self) -> Result<&mut ThreadSection> {
         match self {
@@ -93,15 +110,64 @@ impl ThreadNode {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Page {
     pub title: String,
     pub loc: Locator,
     pub content: String,
-    pub links: Vec<Locator>,
+    pub links: Vec<PageLink>,
+    /// images and other files referenced by the page
+    pub assets: Vec<Locator>,
+    pub meta: FrontMatter,
+    pub toc: Vec<TocEntry>,
+    /// the page, its file or a section it is in is marked as draft
+    pub draft: bool,
+}
+
+impl Page {
+    /// drafts are left out of the site, unless drafts are enabled
+    pub fn is_hidden(&self) -> bool {
+        self.draft && !crate::config::Config::get().drafts
+    }
+}
+
+/// an internal link, optionally pointing to a heading on the target page
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageLink {
+    pub loc: Locator,
+    pub fragment: Option<String>,
+}
+
+/// a heading of a page, in document order
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TocEntry {
+    pub level: u8,
+    pub id: String,
+    pub title: String,
 }
 
-pub fn read(path: &Path, loc: &Locator, context: &mut Static) -> Result<ThreadSection> {
+/// the result of converting a markdown document
+pub struct RenderedMarkdown {
+    pub html: String,
+    pub links: Vec<PageLink>,
+    pub assets: Vec<Locator>,
+    pub toc: Vec<TocEntry>,
+}
+
+/// internal references collected while rewriting links
+#[derive(Default)]
+struct References {
+    links: Vec<PageLink>,
+    assets: Vec<Locator>,
+}
+
+/// reads a section directory, everything in it is a draft if `parent_draft` is set
+pub fn read(
+    path: &Path,
+    loc: &Locator,
+    parent_draft: bool,
+    context: &mut Static,
+) -> Result<ThreadSection> {
     let index_path = path.join("index.md");
     let section_name = file_title(path)?;
 
@@ -111,23 +177,25 @@ pub fn read(path: &Path, loc: &Locator, context: &mut Static) -> Result<ThreadSe
     {
         loc.clone()
     } else {
-        loc.join(&Locator::new(&section_name))
+        // the directory name, as the title has its underscores replaced
+        loc.join(&Locator::new(
+            &path.file_name().unwrap_or_default().to_string_lossy(),
+        ))
     };
 
     let file_content = read_to_string(&index_path)
         .with_context(|| format!("Failed reading index file {index_path:?}"))?;
-    let (content, page_links) =
-        markdown_to_html(file_content, &loc.join(&Locator::new("index.html"))).with_context(
-            || format!("Failed converting markdown to HTML in file {index_path:?}"),
-        )?;
+    let mut body = parse_page(
+        &file_content,
+        section_name,
+        loc.join(&Locator::new("index.md")),
+    )
+    .with_context(|| format!("Failed converting markdown to HTML in file {index_path:?}"))?;
+    body.draft |= parent_draft || is_draft_path(path);
+    let draft = body.draft;
 
     // make section with index page
-    let mut section = ThreadSection::new(Page {
-        title: section_name.clone(),
-        loc: loc.join(&Locator::new("index.md")),
-        content,
-        links: page_links,
-    });
+    let mut section = ThreadSection::new(body);
 
     // read files, filter index and sort by number
     let files = read_dir(path)
@@ -142,7 +210,24 @@ pub fn read(path: &Path, loc: &Locator, context: &mut Static) -> Result<ThreadSe
                 }
             };
 
-            if entry.path().ends_with("index.md") {
+            if entry.path().ends_with("index.md") || is_hidden(&entry.path()) {
+                return None;
+            }
+
+            let is_file = entry.file_type().is_ok_and(|t| t.is_file());
+            if (is_asset(&entry.path()) && is_file) || is_asset_dir(&entry.path()) {
+                // files of a hidden draft section are not published either
+                if draft && !crate::config::Config::get().drafts {
+                    return None;
+                }
+
+                let name = entry.file_name();
+                let asset_loc = loc.join(&Locator::from_url(&name.to_string_lossy()));
+                if is_file {
+                    context.assets.insert(asset_loc, entry.path());
+                } else if let Err(err) = read_asset_dir(&entry.path(), &asset_loc, context) {
+                    ceprintln!("<yellow>Skipping asset directory with reason:\n{err:?}</yellow>\n");
+                }
                 return None;
             }
 
@@ -156,19 +241,31 @@ pub fn read(path: &Path, loc: &Locator, context: &mut Static) -> Result<ThreadSe
         })
         .collect::<Vec<(u32, DirEntry)>>();
 
-    files.sort_by_key(|(index, _)| *index);
+    // the filename makes the order of siblings with the same index deterministic
+    files.sort_by_key(|(index, entry)| (*index, entry.file_name()));
+
+    let siblings = files
+        .iter()
+        .map(|(_, entry)| entry.path())
+        .chain([index_path.clone()]);
+    for collision in sibling_collisions(&loc, siblings) {
+        match collision {
+            Collision::Locator { .. } => bail!("{collision}"),
+            Collision::OrderIndex { .. } => ceprintln!("<yellow>{collision}</yellow>"),
+        }
+    }
 
     // loop over nodes and add them to the section
     for (_, item) in files {
         let file_type = item.file_type()?;
         if file_type.is_dir() {
-            let child_node = read(&item.path(), &loc, context)?;
+            let child_node = read(&item.path(), &loc, draft, context)?;
             let loc = child_node.body.loc.clone();
             let thread_section = Arc::new(Mutex::new(ThreadNode::Section(child_node)));
             context.reads.insert(loc, thread_section.clone());
             section.children.push(thread_section);
         } else if file_type.is_file() {
-            let page = read_page(&item.path(), &loc)?;
+            let page = read_page(&item.path(), &loc, draft)?;
             let loc = page.loc.clone();
             let thread_node = Arc::new(Mutex::new(ThreadNode::Page(page)));
             section.children.push(Arc::clone(&thread_node));
@@ -181,23 +278,173 @@ pub fn read(path: &Path, loc: &Locator, context: &mut Static) -> Result<ThreadSe
     Ok(section)
 }
 
+/// registers every asset in a directory that is not a section, keeping the names as they are
+fn read_asset_dir(path: &Path, loc: &Locator, context: &mut Static) -> Result<()> {
+    let entries = read_dir(path)
+        .with_context(|| format!("Failed to read dir with path {}", path.display()))?;
+    for entry in entries {
+        let entry = entry?;
+        let asset_loc = loc.join(&Locator::from_url(&entry.file_name().to_string_lossy()));
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() && !is_hidden(&entry.path()) {
+            read_asset_dir(&entry.path(), &asset_loc, context)?;
+        } else if file_type.is_file() && is_asset(&entry.path()) {
+            context.assets.insert(asset_loc, entry.path());
+        }
+    }
+
+    Ok(())
+}
+
 /// given a markdown file path, reads the contents and converts it to HTML
-pub fn read_page(file_path: &PathBuf, loc: &Locator) -> Result<Page> {
+pub fn read_page(file_path: &PathBuf, loc: &Locator, parent_draft: bool) -> Result<Page> {
     let file_content = read_to_string(file_path)
         .with_context(|| format!("Can't read file: '{}'", file_path.display()))?;
-    let (page_content, links) = markdown_to_html(file_content, loc)
+    let page_loc = loc.join(&Locator::new(
+        &file_path
+            .file_name()
+            .ok_or(anyhow!("failed to get filename"))?
+            .to_string_lossy(),
+    ));
+
+    let mut page = parse_page(&file_content, file_title(file_path)?, page_loc)
         .with_context(|| format!("Can't convert markdown to html: '{}'", file_path.display()))?;
+    page.draft |= parent_draft || is_draft_path(file_path);
+
+    Ok(page)
+}
+
+/// parses front matter and markdown into a page
+///
+/// the front matter title takes precedence over the given title derived from the filename
+pub fn parse_page(file_content: &str, file_title: String, loc: Locator) -> Result<Page> {
+    let (meta, markdown) = split_front_matter(file_content)?;
+    let rendered = markdown_to_html(markdown.to_owned(), &loc)?;
 
     Ok(Page {
-        title: file_title(file_path)?,
-        loc: loc.join(&Locator::new(
-            &file_path
-                .file_name()
-                .ok_or(anyhow!("failed to get filename"))?
-                .to_string_lossy(),
-        )),
-        content: page_content,
-        links,
+        title: meta.title.clone().unwrap_or(file_title),
+        loc,
+        content: rendered.html,
+        links: rendered.links,
+        assets: rendered.assets,
+        toc: rendered.toc,
+        draft: meta.draft,
+        meta,
+    })
+}
+
+/// two entries of the same directory that conflict with each other
+pub enum Collision {
+    /// both map to the same url, so one would overwrite the other
+    Locator {
+        loc: Locator,
+        first: PathBuf,
+        second: PathBuf,
+    },
+    /// both have the same order index, so their order is undefined
+    OrderIndex {
+        index: u32,
+        first: PathBuf,
+        second: PathBuf,
+    },
+}
+
+impl Display for Collision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Collision::Locator { loc, first, second } => write!(
+                f,
+                "'{}' and '{}' both map to url {loc}",
+                first.display(),
+                second.display()
+            ),
+            Collision::OrderIndex {
+                index,
+                first,
+                second,
+            } => write!(
+                f,
+                "'{}' and '{}' both have order index {index}",
+                first.display(),
+                second.display()
+            ),
+        }
+    }
+}
+
+impl Collision {
+    /// the path that was found last, which would be skipped or overwrite the first
+    pub fn second(&self) -> &Path {
+        match self {
+            Collision::Locator { second, .. } | Collision::OrderIndex { second, .. } => second,
+        }
+    }
+}
+
+/// finds siblings in a section directory that map to the same locator or share an order index
+pub fn sibling_collisions(
+    section: &Locator,
+    paths: impl IntoIterator<Item = PathBuf>,
+) -> Vec<Collision> {
+    let mut collisions = Vec::new();
+    let mut locators: HashMap<Locator, PathBuf> = HashMap::new();
+    let mut indices: HashMap<u32, PathBuf> = HashMap::new();
+
+    for path in paths {
+        // index.md has no order index
+        if let Ok(index) = file_order_index(&path)
+            && let Some(first) = indices.insert(index, path.clone())
+        {
+            collisions.push(Collision::OrderIndex {
+                index,
+                first,
+                second: path.clone(),
+            });
+        }
+
+        let loc = section.join(&Locator::new(
+            &path.file_name().unwrap_or_default().to_string_lossy(),
+        ));
+        if let Some(first) = locators.insert(loc.clone(), path.clone()) {
+            collisions.push(Collision::Locator {
+                loc,
+                first,
+                second: path,
+            });
+        }
+    }
+
+    collisions
+}
+
+/// files in the content directory that are not markdown are assets, copied as they are
+///
+/// hidden files are ignored
+pub fn is_asset(path: &Path) -> bool {
+    !is_hidden(path) && path.extension().is_some_and(|ext| ext != "md")
+}
+
+/// directories without an order index and index.md, like `images/`, hold assets
+pub fn is_asset_dir(path: &Path) -> bool {
+    path.is_dir()
+        && !is_hidden(path)
+        && file_order_index(path).is_err()
+        && !path.join("index.md").exists()
+}
+
+/// names starting with a dot, like `.DS_Store` or `.git`
+pub fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+/// whether the file or directory name marks a draft, like '3_notes.draft.md'
+pub fn is_draft_path(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| {
+        let name = name.to_string_lossy();
+        name.strip_suffix(".md")
+            .unwrap_or(&name)
+            .ends_with(DRAFT_SUFFIX)
     })
 }
 
@@ -220,7 +467,7 @@ pub fn file_order_index(path: &Path) -> Result<u32> {
 /// gets the title from a filename
 ///
 /// strips leading order index and extension
-fn file_title(path: &Path) -> Result<String> {
+pub fn file_title(path: &Path) -> Result<String> {
     let stem = get_stem(path)?;
     let filename_parts = stem.split("_").skip(1);
 
@@ -233,41 +480,242 @@ fn file_title(path: &Path) -> Result<String> {
 }
 
 fn get_stem(path: &Path) -> Result<&str> {
-    path.file_stem()
+    let stem = path
+        .file_stem()
         .ok_or_else(|| anyhow!("File has no stem: '{}'", path.display()))?
         .to_str()
-        .ok_or_else(|| anyhow!("Filename is not valid UTF-8: '{}'", path.display()))
+        .ok_or_else(|| anyhow!("Filename is not valid UTF-8: '{}'", path.display()))?;
+    Ok(stem.strip_suffix(DRAFT_SUFFIX).unwrap_or(stem))
 }
 
 /// convert markdown into HTML
-pub fn markdown_to_html(content: String, loc: &Locator) -> Result<(String, Vec<Locator>)> {
+pub fn markdown_to_html(content: String, loc: &Locator) -> Result<RenderedMarkdown> {
     let state = markdown_ppp::parser::MarkdownParserState::default();
     let mut doc = parse_markdown(state, &content)
         .map_err(|e| anyhow!("Failed to parse markdown with nom error: {e}"))?;
 
-    let links = rewrite_links(&mut doc.blocks, loc)?;
+    let references = rewrite_links(&mut doc.blocks, loc)?;
+    let toc = anchor_headings(&mut doc.blocks);
+
+    Ok(RenderedMarkdown {
+        html: render_html(&doc, Config::default()),
+        links: references.links,
+        assets: references.assets,
+        toc,
+    })
+}
+
+/// rewrite relative links anywhere inside the markdown to valid relative urls
+fn rewrite_links(blocks: &mut [Block], loc: &Locator) -> Result<References> {
+    let mut references = References::default();
+    rewrite_block_links(blocks, loc, &mut references);
+
+    Ok(references)
+}
+
+fn rewrite_block_links(blocks: &mut [Block], loc: &Locator, refs: &mut References) {
+    for block in blocks {
+        match block {
+            Block::Paragraph(inlines) => rewrite_inline_links(inlines, loc, refs),
+            Block::Heading(heading) => rewrite_inline_links(&mut heading.content, loc, refs),
+            Block::BlockQuote(blocks) => rewrite_block_links(blocks, loc, refs),
+            Block::List(list) => {
+                for item in &mut list.items {
+                    rewrite_block_links(&mut item.blocks, loc, refs);
+                }
+            }
+            Block::Table(table) => {
+                for cell in table.rows.iter_mut().flatten() {
+                    rewrite_inline_links(cell, loc, refs);
+                }
+            }
+            Block::FootnoteDefinition(footnote) => {
+                rewrite_block_links(&mut footnote.blocks, loc, refs)
+            }
+            // reference-style links get their destination from the definition
+            Block::Definition(definition) => {
+                rewrite_destination(&mut definition.destination, loc, refs)
+            }
+            Block::ThematicBreak | Block::CodeBlock(_) | Block::HtmlBlock(_) | Block::Empty => (),
+        }
+    }
+}
+
+fn rewrite_inline_links(inlines: &mut [Inline], loc: &Locator, refs: &mut References) {
+    for inline in inlines {
+        match inline {
+            Inline::Image(image) => rewrite_destination(&mut image.destination, loc, refs),
+            Inline::Link(link) => {
+                rewrite_destination(&mut link.destination, loc, refs);
+                rewrite_inline_links(&mut link.children, loc, refs);
+            }
+            Inline::LinkReference(reference) => {
+                rewrite_inline_links(&mut reference.text, loc, refs)
+            }
+            Inline::Emphasis(children)
+            | Inline::Strong(children)
+            | Inline::Strikethrough(children) => rewrite_inline_links(children, loc, refs),
+            Inline::Text(_)
+            | Inline::LineBreak
+            | Inline::Code(_)
+            | Inline::Html(_)
+            | Inline::Autolink(_)
+            | Inline::FootnoteReference(_)
+            | Inline::Empty => (),
+        }
+    }
+}
+
+/// rewrites a relative link or image destination to an url and records the reference
+fn rewrite_destination(destination: &mut String, loc: &Locator, refs: &mut References) {
+    if destination.contains(":") {
+        return;
+    } // TODO make better
+
+    let (path, fragment) = match destination.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment.to_owned())),
+        None => (destination.as_str(), None),
+    };
+
+    // assets keep their filename, only the order index of section directories is stripped
+    if is_asset(Path::new(path)) {
+        let (dir, name) = path.rsplit_once('/').unwrap_or(("", path));
+        let asset_loc = loc
+            .parent()
+            .join(&Locator::from_asset_dir(dir))
+            .join(&Locator::from_url(name));
+        *destination = match &fragment {
+            Some(fragment) => format!("{asset_loc}#{fragment}"),
+            None => asset_loc.url(),
+        };
+        refs.assets.push(asset_loc);
+        return;
+    }
 
-    Ok((render_html(&doc, Config::default()), links))
+    // a link with only a fragment points to a heading on the same page
+    let (rewritten_loc, url) = if path.is_empty() {
+        (loc.clone(), String::new())
+    } else {
+        let rewritten_loc = loc.parent().join(&Locator::new(path));
+        let url = rewritten_loc.url();
+        (rewritten_loc, url)
+    };
+
+    *destination = match &fragment {
+        Some(fragment) => format!("{url}#{fragment}"),
+        None => url,
+    };
+
+    refs.links.push(PageLink {
+        loc: rewritten_loc,
+        fragment,
+    });
 }
 
-/// rewrite relative links inside the markdown to valid relative urls
-fn rewrite_links(blocks: &mut Vec<Block>, loc: &Locator) -> Result<Vec<Locator>> {
-    let mut internal_links = vec![];
-    for item in blocks {
-        if let Block::Paragraph(p_items) = item {
-            for p_item in p_items {
-                if let Inline::Link(link) = p_item {
-                    if link.destination.contains(":") {
-                        continue;
-                    } // TODO make better
+/// gives every heading a unique id and a permalink, returns the headings in document order
+fn anchor_headings(blocks: &mut [Block]) -> Vec<TocEntry> {
+    // link definitions are needed to render reference links inside headings
+    let definitions: Vec<Block> = blocks
+        .iter()
+        .filter(|block| matches!(block, Block::Definition(_)))
+        .cloned()
+        .collect();
+
+    let mut toc = Vec::new();
+    replace_headings(blocks, &definitions, &mut HashSet::new(), &mut toc);
+    toc
+}
 
-                    let rewritten_loc = loc.parent().join(&Locator::new(&link.destination));
-                    link.destination = rewritten_loc.url();
-                    internal_links.push(rewritten_loc);
+fn replace_headings(
+    blocks: &mut [Block],
+    definitions: &[Block],
+    ids: &mut HashSet<String>,
+    toc: &mut Vec<TocEntry>,
+) {
+    for block in blocks {
+        match block {
+            Block::Heading(heading) => {
+                let level = match heading.kind {
+                    HeadingKind::Atx(level) => level.clamp(1, 6),
+                    HeadingKind::Setext(SetextHeading::Level1) => 1,
+                    HeadingKind::Setext(SetextHeading::Level2) => 2,
+                };
+                let title = inline_text(&heading.content);
+                let id = unique_slug(&title, ids);
+                let inner = inline_html(&heading.content, definitions);
+
+                *block = Block::HtmlBlock(format!(
+                    "<h{level} id=\"{id}\">{inner} <a class=\"anchor\" href=\"#{id}\">#</a></h{level}>"
+                ));
+                toc.push(TocEntry { level, id, title });
+            }
+            Block::BlockQuote(inner) => replace_headings(inner, definitions, ids, toc),
+            Block::List(list) => {
+                for item in &mut list.items {
+                    replace_headings(&mut item.blocks, definitions, ids, toc);
                 }
             }
+            Block::FootnoteDefinition(footnote) => {
+                replace_headings(&mut footnote.blocks, definitions, ids, toc)
+            }
+            _ => (),
+        }
+    }
+}
+
+/// renders inline markdown to HTML without a surrounding paragraph
+fn inline_html(inlines: &[Inline], definitions: &[Block]) -> String {
+    let mut blocks = vec![Block::Paragraph(inlines.to_vec())];
+    blocks.extend_from_slice(definitions);
+
+    let html = render_html(&Document { blocks }, Config::default());
+    let html = html.trim();
+    html.strip_prefix("<p>")
+        .and_then(|html| html.strip_suffix("</p>"))
+        .unwrap_or(html)
+        .to_owned()
+}
+
+/// the text of inline markdown without any formatting
+pub fn inline_text(inlines: &[Inline]) -> String {
+    let mut text = String::new();
+    for inline in inlines {
+        match inline {
+            Inline::Text(t) | Inline::Code(t) | Inline::Autolink(t) => text.push_str(t),
+            Inline::LineBreak => text.push(' '),
+            Inline::Link(link) => text.push_str(&inline_text(&link.children)),
+            Inline::LinkReference(reference) => text.push_str(&inline_text(&reference.text)),
+            Inline::Image(image) => text.push_str(&image.alt),
+            Inline::Emphasis(children)
+            | Inline::Strong(children)
+            | Inline::Strikethrough(children) => text.push_str(&inline_text(children)),
+            Inline::Html(_) | Inline::FootnoteReference(_) | Inline::Empty => (),
         }
     }
+    text
+}
 
-    Ok(internal_links)
+/// turns a heading into a lowercase, dash separated id that is not yet in `ids`
+fn unique_slug(title: &str, ids: &mut HashSet<String>) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = match slug.trim_matches('-') {
+        "" => "section".to_owned(),
+        slug => slug.to_owned(),
+    };
+
+    let mut id = slug.clone();
+    let mut count = 0;
+    while ids.contains(&id) {
+        count += 1;
+        id = format!("{slug}-{count}");
+    }
+    ids.insert(id.clone());
+    id
 }
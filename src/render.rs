@@ -1,35 +1,94 @@
 use std::{
-    collections::HashMap,
-    fs::{File, create_dir_all},
-    io::Write,
+    collections::{HashMap, HashSet},
+    env::current_dir,
+    fs::{
+        File, copy, create_dir_all, read_dir, read_to_string, remove_dir, remove_dir_all,
+        remove_file, symlink_metadata,
+    },
+    io::{ErrorKind, Write},
     ops::Deref,
-    path::Path,
+    path::{Component, Path, PathBuf},
     sync::{Arc, Mutex},
 };
 
-use anyhow::{Context, Result};
-use askama::Template;
+use anyhow::{Context, Result, bail};
+use color_print::ceprintln;
 
 use crate::{
     CONTEXT,
     config::Config,
     locator::Locator,
     reader::{Node, Page, Section, ThreadNode, ThreadNodeType, ThreadSection, read},
-    search::write_index,
-    templates::{BaseTemplate, ContentTableTemplate},
+    search::{SEARCH_DIR, search_collision, write_index},
+    templates::{ContentTableTemplate, LayoutTemplate, NavNode, layout_exists},
 };
 
 impl Page {
     pub fn render(&self, root: &Section) -> Result<String> {
-        let pages = flatten_sections(root);
+        let site = &Config::get().site;
         let content_table = ContentTableTemplate {
-            pages,
-            curr_page: self,
+            site,
+            root: nav_tree(root, self, 0),
         };
 
-        let html = BaseTemplate {
-            table: &content_table,
+        let order = reading_order(root);
+        let position = order.iter().position(|page| page.loc == self.loc);
+        let prev = position
+            .and_then(|i| i.checked_sub(1))
+            .and_then(|i| order.get(i))
+            .copied();
+        let next = position.and_then(|i| order.get(i + 1)).copied();
+
+        let breadcrumbs = breadcrumbs(root, &self.loc).unwrap_or_default();
+        let section = find_section(root, &self.loc);
+        let children = section
+            .map(|section| {
+                section
+                    .children
+                    .iter()
+                    .map(Node::page)
+                    .filter(|page| !page.is_hidden())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let default = match section {
+            Some(_) => "section",
+            None => "page",
+        };
+        // the layout of the page, or the closest default layout of a section it is in
+        let layout = self
+            .meta
+            .layout
+            .as_ref()
+            .or_else(|| {
+                breadcrumbs
+                    .iter()
+                    .rev()
+                    .filter(|page| page.loc != self.loc)
+                    .find_map(|page| page.meta.default_layout.as_ref())
+            })
+            .map_or(default, String::as_str);
+        let layout = if layout_exists(layout) {
+            layout
+        } else {
+            ceprintln!(
+                "<yellow>Layout '{layout}' of '{}' does not exist, using '{default}'</yellow>",
+                self.loc
+            );
+            default
+        };
+
+        let html = LayoutTemplate {
+            site,
+            layout: String::from(layout),
+            table: content_table.render()?,
             page: self,
+            children,
+            breadcrumbs,
+            prev,
+            next,
+            live_reload: Config::get().live_reload,
         }
         .render()
         .with_context(|| {
@@ -45,18 +104,76 @@ impl Page {
     }
 }
 
-fn flatten_sections(root: &Section) -> Vec<&Page> {
-    let mut pages = Vec::new();
-    pages.push(&root.body);
-    for node in &root.children {
+/// builds the navigation tree of a section, marking the current page and its ancestors
+fn nav_tree<'a>(section: &'a Section, curr_page: &Page, depth: usize) -> NavNode<'a> {
+    let children: Vec<NavNode> = section
+        .children
+        .iter()
+        .filter(|node| !node.page().is_hidden())
+        .map(|node| match node {
+            Node::Section(section) => nav_tree(section, curr_page, depth + 1),
+            Node::Page(page) => NavNode {
+                page,
+                depth: depth + 1,
+                is_ancestor: false,
+                is_current: page.loc == curr_page.loc,
+                children: Vec::new(),
+            },
+        })
+        .collect();
+
+    NavNode {
+        page: &section.body,
+        depth,
+        is_ancestor: children.iter().any(|c| c.is_current || c.is_ancestor),
+        is_current: section.body.loc == curr_page.loc,
+        children,
+    }
+}
+
+/// all pages in reading order, every section starts with its index page
+fn reading_order(section: &Section) -> Vec<&Page> {
+    let mut pages = vec![&section.body];
+    for node in section
+        .children
+        .iter()
+        .filter(|node| !node.page().is_hidden())
+    {
         match node {
-            Node::Section(section) => pages.append(&mut flatten_sections(section)),
+            Node::Section(section) => pages.append(&mut reading_order(section)),
             Node::Page(page) => pages.push(page),
         };
     }
     pages
 }
 
+/// the index pages of all sections from the root down to the page with the given locator,
+/// ending with the page itself
+fn breadcrumbs<'a>(section: &'a Section, loc: &Locator) -> Option<Vec<&'a Page>> {
+    if section.body.loc == *loc {
+        return Some(vec![&section.body]);
+    }
+
+    let mut rest = section.children.iter().find_map(|node| match node {
+        Node::Section(child) => breadcrumbs(child, loc),
+        Node::Page(page) => (page.loc == *loc).then(|| vec![page]),
+    })?;
+    rest.insert(0, &section.body);
+    Some(rest)
+}
+
+/// the section that has the page with the given locator as index page
+fn find_section<'a>(section: &'a Section, loc: &Locator) -> Option<&'a Section> {
+    if section.body.loc == *loc {
+        return Some(section);
+    }
+
+    section.children.iter().find_map(|node| match node {
+        Node::Section(child) => find_section(child, loc),
+        Node::Page(_) => None,
+    })
+}
+
 pub fn get_root(reads: &HashMap<Locator, ThreadNodeType>) -> Result<Section> {
     let root = reads
         .get(&Locator::root()?)
@@ -65,18 +182,32 @@ pub fn get_root(reads: &HashMap<Locator, ThreadNodeType>) -> Result<Section> {
     Ok(root.lock().unwrap().get_section()?.into())
 }
 
-/// write all rendered pages to files
-pub fn write_pages_to_files() -> Result<()> {
+/// write all rendered pages, assets, static files and the search index to the public directory
+///
+/// files left over from earlier builds are removed, with `clean` the directory is emptied first
+pub fn write_pages_to_files(clean: bool) -> Result<()> {
+    if clean {
+        clean_public_dir()?;
+    }
+
     let context = CONTEXT.lock().unwrap();
 
     let root = get_root(&context.reads)?;
 
+    // every file written, to detect static files that would overwrite them
+    let mut generated = HashSet::new();
+
     for (loc, node) in context.reads.iter() {
         let page = match node.lock().unwrap().deref() {
             ThreadNode::Section(section) => section.body.clone(),
             ThreadNode::Page(page) => page.clone(),
         };
 
+        if page.is_hidden() {
+            println!("Skipping draft {}", loc.url());
+            continue;
+        }
+
         let html = page.render(&root)?;
         create_dir_all(loc.public_dir()).with_context(|| {
             format!(
@@ -100,19 +231,258 @@ pub fn write_pages_to_files() -> Result<()> {
             loc.url(),
             loc.public_path().display()
         );
+        generated.insert(loc.public_path());
+    }
+
+    for (loc, source) in context.assets.iter() {
+        // assets keep their name, so the public dir of their locator is the file itself
+        let target = loc.public_dir();
+        copy_file(source, &target)?;
+
+        println!("Copied asset {} to {}", loc.url(), target.display());
+        generated.insert(target);
+    }
+
+    if let Some(loc) = search_collision(&context) {
+        bail!("'{loc}' collides with the search index in '/{SEARCH_DIR}'");
+    }
+    generated.extend(write_index(&context.reads)?);
+
+    copy_static_dir(&mut generated)?;
+    remove_stale_files(&generated)?;
+
+    Ok(())
+}
+
+/// checks that the public directory can be deleted from without losing anything
+///
+/// it has to be inside the project, without being the project itself or containing sources
+fn check_public_dir_removable(public: &Path) -> Result<()> {
+    let public = public.canonicalize()?;
+    let project = current_dir()?.canonicalize()?;
+    if public == project || !public.starts_with(&project) {
+        bail!(
+            "Refusing to delete files in '{}', the public directory is not inside the project '{}'",
+            public.display(),
+            project.display()
+        );
+    }
+
+    for source in [&Config::get().content, &Config::get().static_dir] {
+        if let Ok(source) = source.canonicalize()
+            && source.starts_with(&public)
+        {
+            bail!(
+                "Refusing to delete files in '{}', it contains '{}'",
+                public.display(),
+                source.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// removes the whole public directory
+fn clean_public_dir() -> Result<()> {
+    let public = Path::new(&Config::get().public);
+    if !public.exists() {
+        return Ok(());
+    }
+
+    check_public_dir_removable(public)?;
+    remove_dir_all(public)
+        .with_context(|| format!("Failed to remove directory: '{}'", public.display()))?;
+    println!("Cleaned {}", public.display());
+
+    Ok(())
+}
+
+/// file in the public directory listing everything the last build wrote, one path per line
+static BUILD_MANIFEST: &str = ".ligny-manifest";
+
+/// removes files that an earlier build wrote and this build did not
+///
+/// only files in the manifest of the earlier build are removed, anything else in the public
+/// directory is left alone
+fn remove_stale_files(generated: &HashSet<PathBuf>) -> Result<()> {
+    let public = Path::new(&Config::get().public);
+    if !public.exists() {
+        return Ok(());
+    }
+
+    match check_public_dir_removable(public) {
+        Ok(()) => update_build_manifest(public, generated),
+        Err(err) => {
+            ceprintln!("<yellow>Not removing stale files: {err}</yellow>");
+            write_build_manifest(public, generated)
+        }
+    }
+}
+
+/// removes the files of the previous manifest that are not generated anymore and writes the
+/// new manifest
+fn update_build_manifest(public: &Path, generated: &HashSet<PathBuf>) -> Result<()> {
+    let manifest = public.join(BUILD_MANIFEST);
+    let previous = match read_to_string(&manifest) {
+        Ok(previous) => previous,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("Failed to read file: '{}'", manifest.display()));
+        }
+    };
+
+    for line in previous.lines() {
+        let relative = Path::new(line);
+        let path = public.join(relative);
+        if generated.contains(&path) || !is_removable(public, relative) {
+            continue;
+        }
+
+        remove_file(&path)
+            .with_context(|| format!("Failed to remove file: '{}'", path.display()))?;
+        println!("Removed stale file {}", path.display());
+        remove_empty_parents(public, &path);
+    }
+
+    write_build_manifest(public, generated)
+}
+
+fn write_build_manifest(public: &Path, generated: &HashSet<PathBuf>) -> Result<()> {
+    let mut files: Vec<String> = generated
+        .iter()
+        .filter_map(|path| path.strip_prefix(public).ok())
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+
+    let manifest = public.join(BUILD_MANIFEST);
+    let mut file = File::create(&manifest)
+        .with_context(|| format!("Failed to create file: '{}'", manifest.display()))?;
+    for path in files {
+        writeln!(file, "{path}")
+            .with_context(|| format!("Failed to write to file: '{}'", manifest.display()))?;
+    }
+
+    Ok(())
+}
+
+/// whether a path from the manifest is a plain file inside the public directory
+///
+/// hidden files and anything reached through a symlink are never removed, as they were not put
+/// there by a build
+fn is_removable(public: &Path, relative: &Path) -> bool {
+    let mut path = public.to_path_buf();
+    for component in relative.components() {
+        let Component::Normal(name) = component else {
+            return false;
+        };
+        if name.to_string_lossy().starts_with('.') {
+            return false;
+        }
+
+        path.push(name);
+        if symlink_metadata(&path).is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+            return false;
+        }
+    }
+
+    symlink_metadata(&path).is_ok_and(|metadata| metadata.is_file())
+}
+
+/// removes the directories of a removed file that are empty now, up to the public directory
+fn remove_empty_parents(public: &Path, path: &Path) {
+    for dir in path.ancestors().skip(1) {
+        if dir == public || !dir.starts_with(public) || remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
+
+/// mirror the static directory into the public directory, adding the copies to `generated`
+///
+/// fails without copying anything if a static file has the same path as a generated file
+fn copy_static_dir(generated: &mut HashSet<PathBuf>) -> Result<()> {
+    let static_dir = &Config::get().static_dir;
+    if !static_dir.is_dir() {
+        return Ok(());
+    }
+
+    let public = Path::new(&Config::get().public);
+    let files = files_in_dir(static_dir)?;
+
+    for file in &files {
+        let target = public.join(file);
+        if generated.contains(&target) {
+            bail!(
+                "Static file '{}' collides with generated file '{}'",
+                static_dir.join(file).display(),
+                target.display()
+            );
+        }
+    }
+
+    for file in &files {
+        let target = public.join(file);
+        copy_file(&static_dir.join(file), &target)?;
+        println!(
+            "Copied static file {} to {}",
+            file.display(),
+            target.display()
+        );
+        generated.insert(target);
     }
 
-    write_index(&context.reads)?;
+    Ok(())
+}
+
+fn copy_file(source: &Path, target: &Path) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        create_dir_all(parent).with_context(|| {
+            format!("Failed to create all dirs for path: '{}'", parent.display())
+        })?;
+    }
+
+    copy(source, target).with_context(|| {
+        format!(
+            "Failed to copy '{}' to '{}'",
+            source.display(),
+            target.display()
+        )
+    })?;
 
     Ok(())
 }
 
+/// all files in a directory and its subdirectories, relative to that directory
+fn files_in_dir(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in
+        read_dir(dir).with_context(|| format!("Failed to read dir with path {}", dir.display()))?
+    {
+        let path = entry?.path();
+        let relative = path.strip_prefix(dir)?.to_path_buf();
+        if path.is_dir() {
+            files.extend(
+                files_in_dir(&path)?
+                    .into_iter()
+                    .map(|file| relative.join(file)),
+            );
+        } else {
+            files.push(relative);
+        }
+    }
+    Ok(files)
+}
+
 /// read all files in the content directory and render them using templates to memory
 pub fn read_files() -> Result<()> {
     let mut context = CONTEXT.lock().unwrap();
     let root: ThreadSection = read(
         Path::new(&Config::get().content),
         &Locator::new(""),
+        false,
         &mut context,
     )?;
 
@@ -123,3 +493,67 @@ pub fn read_files() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// an empty directory for a test, removed first if an earlier run left it behind
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ligny-{name}-{}", std::process::id()));
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(path: &Path) {
+        create_dir_all(path.parent().unwrap()).unwrap();
+        File::create(path).unwrap();
+    }
+
+    #[test]
+    fn user_files_in_public_survive_a_rebuild() {
+        let public = test_dir("rebuild");
+        let page = public.join("old/index.html");
+        write(&page);
+        update_build_manifest(&public, &HashSet::from([page.clone()])).unwrap();
+
+        for file in ["CNAME", ".git/HEAD", "old/notes.txt"] {
+            write(&public.join(file));
+        }
+        let new_page = public.join("new/index.html");
+        write(&new_page);
+        update_build_manifest(&public, &HashSet::from([new_page.clone()])).unwrap();
+
+        assert!(!page.exists());
+        assert!(new_page.exists());
+        for file in ["CNAME", ".git/HEAD", "old/notes.txt"] {
+            assert!(public.join(file).exists(), "{file} was removed");
+        }
+        assert_eq!(
+            read_to_string(public.join(BUILD_MANIFEST)).unwrap(),
+            "new/index.html\n"
+        );
+
+        remove_dir_all(public).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stale_files_are_not_removed_through_symlinks() {
+        let public = test_dir("symlink");
+        let outside = test_dir("symlink-outside");
+        write(&outside.join("file.txt"));
+        std::os::unix::fs::symlink(&outside, public.join("link")).unwrap();
+
+        let mut manifest = File::create(public.join(BUILD_MANIFEST)).unwrap();
+        let name = outside.file_name().unwrap().to_string_lossy();
+        writeln!(manifest, "link/file.txt\n../{name}/file.txt").unwrap();
+        update_build_manifest(&public, &HashSet::new()).unwrap();
+
+        assert!(outside.join("file.txt").exists());
+
+        remove_dir_all(public).unwrap();
+        remove_dir_all(outside).unwrap();
+    }
+}
@@ -1,46 +1,182 @@
-use std::{collections::HashMap, fs::File, io::Write, ops::Deref, path::Path};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::{File, create_dir_all},
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Context, Result};
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 
 use crate::{
+    Static,
     config::Config,
     locator::Locator,
-    reader::{ThreadNode, ThreadNodeType},
+    reader::{Node, Page, Section, ThreadNodeType},
+    render::get_root,
 };
 
+/// the most hits returned for a query
+const MAX_HITS: usize = 20;
+
+/// how many bytes of text a snippet shows before the first match
+const SNIPPET_BEFORE: usize = 60;
+
+/// how many bytes of text a snippet has at most
+const SNIPPET_LENGTH: usize = 200;
+
+/// a heading counts as this many occurrences of its words
+const HEADING_WEIGHT: u32 = 3;
+
+/// the page title counts as this many occurrences of its words
+const TITLE_WEIGHT: u32 = 2;
+
+/// keywords from the front matter count as this many occurrences
+const KEYWORD_WEIGHT: u32 = 3;
+
+/// the static index puts terms in shards by this many of their first characters
+const SHARD_PREFIX_LENGTH: usize = 2;
+
+/// the static index puts the text of this many documents in each file of `texts/`
+const TEXT_BATCH_SIZE: usize = 50;
+
+/// directory in the public directory with the static search index
+///
+/// it is not `search`, so a page with that name does not share a directory with the index
+pub static SEARCH_DIR: &str = "_search";
+
+/// elements that do not separate words, all others do
+const INLINE_ELEMENTS: [&str; 16] = [
+    "a", "abbr", "b", "code", "del", "em", "i", "kbd", "mark", "s", "small", "span", "strong",
+    "sub", "sup", "u",
+];
+
+/// a part of a page in the search index, from one heading up to the next
 #[derive(Serialize)]
-pub struct SearchPage {
-    html: String,
+pub struct SearchChunk {
+    /// url of the page the chunk is on
     url: String,
+    /// id of the heading the chunk starts with
+    anchor: Option<String>,
     title: String,
+    heading: Option<String>,
+    /// titles of the sections the page is in, without the root
+    breadcrumb: Vec<String>,
+    /// the chunk as plain text
+    text: String,
+    /// scores are multiplied by this
+    #[serde(skip_serializing_if = "is_default_weight")]
+    weight: f64,
+    /// words the page can be found by besides its text
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    keywords: Vec<String>,
 }
 
-type Index = Vec<SearchPage>;
+fn is_default_weight(weight: &f64) -> bool {
+    *weight == 1.0
+}
+
+type Index = Vec<SearchChunk>;
 
 pub fn render_index(reads: &HashMap<Locator, ThreadNodeType>) -> Result<Index> {
+    let root = get_root(reads)?;
     let mut index = vec![];
-    for (loc, node) in reads.iter() {
-        let page = match node.lock().unwrap().deref() {
-            ThreadNode::Section(section) => section.body.clone(),
-            ThreadNode::Page(page) => page.clone(),
-        };
 
-        index.push(SearchPage {
-            html: page.content.clone(),
-            url: loc.url().clone(),
+    // the root is in every breadcrumb, so it is left out to keep them short
+    page_chunks(&root.body, true, &[], &mut index);
+    children_chunks(&root, &[], &mut index);
+
+    Ok(index)
+}
+
+/// adds the chunks of everything in the section, in reading order
+fn children_chunks(section: &Section, breadcrumb: &[String], index: &mut Index) {
+    for node in &section.children {
+        match node {
+            Node::Section(child) => {
+                page_chunks(&child.body, true, breadcrumb, index);
+
+                let mut breadcrumb = breadcrumb.to_vec();
+                breadcrumb.push(child.body.title.clone());
+                children_chunks(child, &breadcrumb, index);
+            }
+            Node::Page(page) => page_chunks(page, false, breadcrumb, index),
+        }
+    }
+}
+
+fn page_chunks(page: &Page, is_index: bool, breadcrumb: &[String], index: &mut Index) {
+    if page.is_hidden() || is_excluded(page) {
+        return;
+    }
+
+    let mut chunks = html_to_chunks(&page.content);
+    if chunks.iter().all(|chunk| chunk.text.is_empty()) {
+        // the index page of a section is often only there to group its pages
+        if is_index {
+            return;
+        }
+
+        // a page without text can still be found by its title
+        chunks = vec![TextChunk::new(None, None)];
+    }
+
+    for chunk in chunks {
+        index.push(SearchChunk {
+            url: page.loc.url(),
+            anchor: chunk.anchor,
             title: page.title.clone(),
+            heading: chunk.heading,
+            breadcrumb: breadcrumb.to_vec(),
+            text: chunk.text,
+            weight: page.meta.search.weight,
+            keywords: page.meta.search.keywords.clone(),
         });
     }
+}
 
-    Ok(index)
+/// pages are left out of search by their front matter or the exclusions in the config
+fn is_excluded(page: &Page) -> bool {
+    page.meta.search.exclude
+        || Config::get()
+            .search
+            .exclude
+            .iter()
+            .any(|url| page.loc.starts_with(&Locator::from_url(url)))
+}
+
+/// a page or asset with its url in the search directory, which the search index would hide
+pub fn search_collision(context: &Static) -> Option<Locator> {
+    let search = Locator::from_url(SEARCH_DIR);
+    context
+        .reads
+        .keys()
+        .chain(context.assets.keys())
+        .find(|loc| loc.starts_with(&search))
+        .cloned()
 }
 
-pub fn write_index(reads: &HashMap<Locator, ThreadNodeType>) -> Result<()> {
+/// writes the index and the static search index to the public directory
+///
+/// the index with the text of every chunk is not used by the site itself, it is still written
+/// as the `index` setting lets other tools read the content of the site from it
+///
+/// returns the paths of all files written
+pub fn write_index(reads: &HashMap<Locator, ThreadNodeType>) -> Result<Vec<PathBuf>> {
     let index = render_index(reads)?;
-    let json = serde_json::to_string(&index)?;
     let path = Path::new(&Config::get().public).join(&Config::get().index_name);
-    let mut file = File::create(&path)
+    write_json(&path, &index)?;
+
+    let mut written =
+        SearchIndex::new(index).write_shards(&Path::new(&Config::get().public).join(SEARCH_DIR))?;
+    written.push(path);
+
+    Ok(written)
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+    let json = serde_json::to_string(value)?;
+    let mut file = File::create(path)
         .with_context(|| format!("Failed to create file: '{}'", path.display()))?;
 
     file.write_all(json.as_bytes())
@@ -48,3 +184,511 @@ pub fn write_index(reads: &HashMap<Locator, ThreadNodeType>) -> Result<()> {
 
     Ok(())
 }
+
+/// the part of a page from one heading up to the next, as plain text
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextChunk {
+    /// id of the heading the chunk starts with, none for the text before the first heading
+    pub anchor: Option<String>,
+    pub heading: Option<String>,
+    pub text: String,
+}
+
+impl TextChunk {
+    fn new(anchor: Option<String>, heading: Option<String>) -> Self {
+        TextChunk {
+            anchor,
+            heading,
+            text: String::new(),
+        }
+    }
+}
+
+/// strips the tags from rendered html, splitting the text at every heading with an id
+///
+/// chunks without a heading or text are left out
+pub fn html_to_chunks(html: &str) -> Vec<TextChunk> {
+    let mut chunks = vec![TextChunk::new(None, None)];
+    // the heading that is being read, with its id
+    let mut heading: Option<(String, String)> = None;
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        let text = decode_entities(&rest[..start]);
+        match &mut heading {
+            Some((_, title)) => title.push_str(&text),
+            None => chunks.last_mut().unwrap().text.push_str(&text),
+        }
+
+        let Some(length) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start + 1..start + length];
+        rest = &rest[start + length + 1..];
+
+        let name = tag_name(tag);
+        if let Some(close) = skipped_element(&name, tag) {
+            rest = rest.find(&close).map_or("", |i| &rest[i + close.len()..]);
+            continue;
+        }
+
+        let is_heading =
+            name.len() == 2 && name.starts_with('h') && name.as_bytes()[1].is_ascii_digit();
+        if is_heading && !tag.starts_with('/') {
+            if let Some(id) = attribute(tag, "id") {
+                heading = Some((id, String::new()));
+                continue;
+            }
+        } else if is_heading && let Some((id, title)) = heading.take() {
+            chunks.push(TextChunk::new(Some(id), Some(collapse_whitespace(&title))));
+            continue;
+        }
+
+        if !INLINE_ELEMENTS.contains(&name.as_str()) {
+            match &mut heading {
+                Some((_, title)) => title.push(' '),
+                None => chunks.last_mut().unwrap().text.push(' '),
+            }
+        }
+    }
+    chunks
+        .last_mut()
+        .unwrap()
+        .text
+        .push_str(&decode_entities(rest));
+
+    chunks
+        .into_iter()
+        .map(|chunk| TextChunk {
+            text: collapse_whitespace(&chunk.text),
+            ..chunk
+        })
+        .filter(|chunk| chunk.heading.is_some() || !chunk.text.is_empty())
+        .collect()
+}
+
+/// the lowercase name of a tag, without the slash of a closing tag
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches('/')
+        .split(|c: char| c.is_whitespace() || c == '/')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// the closing tag of elements whose content is not text of the page
+fn skipped_element(name: &str, tag: &str) -> Option<String> {
+    match name {
+        "script" | "style" => Some(format!("</{name}>")),
+        // the '#' link that is added to every heading
+        "a" if attribute(tag, "class").is_some_and(|class| class == "anchor") => {
+            Some(String::from("</a>"))
+        }
+        _ => None,
+    }
+}
+
+/// the value of an attribute in double quotes
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let start = tag.find(&format!(" {name}=\""))? + name.len() + 3;
+    let length = tag[start..].find('"')?;
+    Some(decode_entities(&tag[start..start + length]))
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| Some((decode_entity(&rest[1..end])?, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => entity.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// splits text into lowercase words, with the byte offset each word starts at
+fn tokenize(text: &str) -> Vec<(usize, String)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (start, c.is_alphanumeric()) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                tokens.push((s, text[s..i].to_lowercase()));
+                start = None;
+            }
+            _ => (),
+        }
+    }
+
+    tokens
+}
+
+/// how often a term occurs in a document, weighted by where it occurs
+pub struct Posting {
+    doc: usize,
+    frequency: u32,
+}
+
+/// postings are written as `[doc, frequency]`, as there are a lot of them
+impl Serialize for Posting {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.doc, self.frequency).serialize(serializer)
+    }
+}
+
+/// what every search in the static index needs, the shards and texts are loaded when needed
+#[derive(Serialize)]
+pub struct SearchManifest<'a> {
+    prefix_length: usize,
+    /// the keys of all shards, a shard is in `terms/<key>.json`
+    shards: BTreeSet<String>,
+    /// the text of document `i` is in `texts/<i / text_batch_size>.json`
+    text_batch_size: usize,
+    docs: Vec<ManifestDoc<'a>>,
+}
+
+/// a chunk without its text, which is only needed to show a result
+#[derive(Serialize)]
+pub struct ManifestDoc<'a> {
+    url: &'a str,
+    anchor: &'a Option<String>,
+    title: &'a str,
+    heading: &'a Option<String>,
+    breadcrumb: &'a [String],
+    #[serde(skip_serializing_if = "is_default_weight")]
+    weight: f64,
+}
+
+type Shard<'a> = BTreeMap<&'a str, &'a Vec<Posting>>;
+
+/// an inverted index over the text of all pages, split at headings
+pub struct SearchIndex {
+    docs: Vec<SearchChunk>,
+    /// sorted, so terms starting with a prefix are next to each other
+    terms: BTreeMap<String, Vec<Posting>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SearchHit {
+    /// url of the page, with the anchor of the heading if the hit is below one
+    pub url: String,
+    pub title: String,
+    pub heading: Option<String>,
+    pub anchor: Option<String>,
+    pub breadcrumb: Vec<String>,
+    /// the text around the first match
+    pub snippet: String,
+    pub score: f64,
+}
+
+impl SearchIndex {
+    pub fn new(chunks: Index) -> Self {
+        let mut index = SearchIndex {
+            docs: Vec::new(),
+            terms: BTreeMap::new(),
+        };
+
+        for chunk in chunks {
+            index.add(chunk);
+        }
+
+        index
+    }
+
+    fn add(&mut self, doc: SearchChunk) {
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        let fields = [
+            (doc.text.as_str(), 1),
+            (doc.heading.as_deref().unwrap_or_default(), HEADING_WEIGHT),
+            (doc.title.as_str(), TITLE_WEIGHT),
+        ]
+        .into_iter()
+        .chain(
+            doc.keywords
+                .iter()
+                .map(|keyword| (keyword.as_str(), KEYWORD_WEIGHT)),
+        );
+        for (text, weight) in fields {
+            for (_, token) in tokenize(text) {
+                *frequencies.entry(token).or_default() += weight;
+            }
+        }
+
+        let id = self.docs.len();
+        for (term, frequency) in frequencies {
+            self.terms
+                .entry(term)
+                .or_default()
+                .push(Posting { doc: id, frequency });
+        }
+        self.docs.push(doc);
+    }
+
+    pub fn manifest(&self) -> SearchManifest<'_> {
+        SearchManifest {
+            prefix_length: SHARD_PREFIX_LENGTH,
+            shards: self.terms.keys().map(|term| shard_key(term)).collect(),
+            text_batch_size: TEXT_BATCH_SIZE,
+            docs: self
+                .docs
+                .iter()
+                .map(|doc| ManifestDoc {
+                    url: &doc.url,
+                    anchor: &doc.anchor,
+                    title: &doc.title,
+                    heading: &doc.heading,
+                    breadcrumb: &doc.breadcrumb,
+                    weight: doc.weight,
+                })
+                .collect(),
+        }
+    }
+
+    /// the texts of the documents in a batch, in order
+    pub fn texts(&self, batch: usize) -> Vec<&str> {
+        self.docs
+            .iter()
+            .skip(batch * TEXT_BATCH_SIZE)
+            .take(TEXT_BATCH_SIZE)
+            .map(|doc| doc.text.as_str())
+            .collect()
+    }
+
+    /// the terms with the given shard key, with their postings
+    pub fn shard(&self, key: &str) -> Shard<'_> {
+        self.terms
+            .iter()
+            .filter(|(term, _)| shard_key(term) == key)
+            .map(|(term, postings)| (term.as_str(), postings))
+            .collect()
+    }
+
+    /// writes the manifest to `docs.json`, every shard to `terms/` and the texts to `texts/`, in
+    /// the given directory
+    ///
+    /// returns the paths of all files written
+    pub fn write_shards(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let terms_dir = dir.join("terms");
+        let texts_dir = dir.join("texts");
+        for dir in [&terms_dir, &texts_dir] {
+            create_dir_all(dir)
+                .with_context(|| format!("Failed to create dir: '{}'", dir.display()))?;
+        }
+
+        let path = dir.join("docs.json");
+        write_json(&path, &self.manifest())?;
+        let mut written = vec![path];
+
+        let mut shards: BTreeMap<String, Shard> = BTreeMap::new();
+        for (term, postings) in &self.terms {
+            shards
+                .entry(shard_key(term))
+                .or_default()
+                .insert(term, postings);
+        }
+        for (key, shard) in shards {
+            let path = terms_dir.join(format!("{key}.json"));
+            write_json(&path, &shard)?;
+            written.push(path);
+        }
+
+        for batch in 0..self.docs.len().div_ceil(TEXT_BATCH_SIZE) {
+            let path = texts_dir.join(format!("{batch}.json"));
+            write_json(&path, &self.texts(batch))?;
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+
+    /// the documents containing every word of the query, best first
+    ///
+    /// the last word also matches longer words starting with it, as it might not be typed out
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let words: Vec<String> = tokenize(query).into_iter().map(|(_, word)| word).collect();
+        let Some(last) = words.len().checked_sub(1) else {
+            return Vec::new();
+        };
+
+        let mut scores: HashMap<usize, (usize, f64)> = HashMap::new();
+        for (i, word) in words.iter().enumerate() {
+            // the best score of all terms the word matches, per document
+            let mut word_scores: HashMap<usize, f64> = HashMap::new();
+            let terms: Vec<&Vec<Posting>> = if i == last {
+                self.terms
+                    .range(word.clone()..)
+                    .take_while(|(term, _)| term.starts_with(word.as_str()))
+                    .map(|(_, postings)| postings)
+                    .collect()
+            } else {
+                self.terms.get(word).into_iter().collect()
+            };
+
+            for postings in terms {
+                let idf = (1.0 + self.docs.len() as f64 / postings.len() as f64).ln();
+                for posting in postings {
+                    let score = word_scores.entry(posting.doc).or_default();
+                    *score = score.max(posting.frequency as f64 * idf);
+                }
+            }
+
+            for (doc, score) in word_scores {
+                let total = scores.entry(doc).or_default();
+                total.0 += 1;
+                total.1 += score;
+            }
+        }
+
+        let mut hits: Vec<(usize, f64)> = scores
+            .into_iter()
+            .filter(|(_, (matched, _))| *matched == words.len())
+            .map(|(doc, (_, score))| (doc, score * self.docs[doc].weight))
+            .collect();
+        hits.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        hits.into_iter()
+            .take(MAX_HITS)
+            .map(|(doc, score)| {
+                let doc = &self.docs[doc];
+                SearchHit {
+                    url: match &doc.anchor {
+                        Some(anchor) => format!("{}#{anchor}", doc.url),
+                        None => doc.url.clone(),
+                    },
+                    title: doc.title.clone(),
+                    heading: doc.heading.clone(),
+                    anchor: doc.anchor.clone(),
+                    breadcrumb: doc.breadcrumb.clone(),
+                    snippet: snippet(&doc.text, &words),
+                    score,
+                }
+            })
+            .collect()
+    }
+}
+
+/// the shard a term is in, named after the first characters of the term
+///
+/// characters other than ascii letters and digits are written as `_` and their hex code point,
+/// so the key can be used as a file name
+pub fn shard_key(term: &str) -> String {
+    term.chars()
+        .take(SHARD_PREFIX_LENGTH)
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' => c.to_string(),
+            _ => format!("_{:x}", c as u32),
+        })
+        .collect()
+}
+
+/// the text around the first word that matches the query, cut at word boundaries
+fn snippet(text: &str, words: &[String]) -> String {
+    let first_match = tokenize(text)
+        .into_iter()
+        .find(|(_, token)| words.iter().any(|word| token.starts_with(word.as_str())))
+        .map_or(0, |(offset, _)| offset);
+
+    let mut start = first_match.saturating_sub(SNIPPET_BEFORE);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    if start > 0 {
+        start = text[start..first_match]
+            .find(' ')
+            .map_or(first_match, |space| start + space + 1);
+    }
+
+    let mut end = (start + SNIPPET_LENGTH).min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    if end < text.len() {
+        end = text[start..end]
+            .rfind(' ')
+            .map_or(end, |space| start + space);
+    }
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    snippet.push_str(&text[start..end]);
+    if end < text.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        tokenize(text).into_iter().map(|(_, word)| word).collect()
+    }
+
+    /// `tokenize` and `shardKey` in search.js have to give the same results
+    #[test]
+    fn non_ascii_tokens_and_shard_keys() {
+        assert_eq!(
+            words("हिन्दी Café Ünïcode x²3"),
+            ["हिन", "दी", "café", "ünïcode", "x²3"]
+        );
+
+        let keys: Vec<String> = words("हिन्दी Café Ünïcode x²3")
+            .iter()
+            .map(|word| shard_key(word))
+            .collect();
+        assert_eq!(keys, ["_939_93f", "_926_940", "ca", "_fcn", "x_b2"]);
+    }
+
+    #[test]
+    fn token_offsets() {
+        assert_eq!(
+            tokenize("Hello, wörld!"),
+            [(0, String::from("hello")), (7, String::from("wörld"))]
+        );
+    }
+}
@@ -1,8 +1,8 @@
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::Read;
 use std::net::SocketAddr;
 use std::ops::Deref;
+use std::path::Path;
+use std::process::Command;
 
 use color_print::ceprintln;
 use futures_util::TryStreamExt;
@@ -16,7 +16,11 @@ use hyper::body::Bytes;
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
 use hyper_util::rt::TokioIo;
+use mime_guess::mime;
+use serde::Serialize;
+use tokio::fs::File;
 use tokio::net::TcpListener;
+use tokio_util::io::ReaderStream;
 
 use anyhow::{Context, Result, anyhow};
 use tokio::sync::broadcast::{self, Sender};
@@ -26,20 +30,28 @@ use crate::locator::Locator;
 use crate::reader::ThreadNode;
 use crate::reader::ThreadNodeType;
 use crate::render::get_root;
-use crate::search::render_index;
+use crate::search::{SEARCH_DIR, SearchIndex, render_index, search_collision};
 use crate::watcher::spawn_watcher_thread;
-use crate::{CONTEXT, Config};
+use crate::{CONTEXT, Config, Static};
 
-pub async fn serve() -> Result<()> {
+pub async fn serve(open: bool) -> Result<()> {
     let addr: SocketAddr = SocketAddr::from((Config::get().address, Config::get().port));
 
     println!("Listening on {addr}");
 
     let (tx, _rx) = broadcast::channel(32);
 
+    if let Some(loc) = search_collision(&CONTEXT.lock().unwrap()) {
+        ceprintln!("<yellow>'{loc}' is hidden by the search index in '/{SEARCH_DIR}'</yellow>");
+    }
+
     let _ = spawn_watcher_thread(tx.clone());
 
     let listener = TcpListener::bind(addr).await?;
+    if open {
+        open_browser(&format!("http://{addr}"));
+    }
+
     loop {
         let (stream, _) = listener.accept().await?;
         let io = TokioIo::new(stream);
@@ -57,7 +69,26 @@ pub async fn serve() -> Result<()> {
     }
 }
 
+/// opens the url with the default browser of the platform
+fn open_browser(url: &str) {
+    let opener = if cfg!(target_os = "macos") {
+        Command::new("open").arg(url).spawn()
+    } else if cfg!(target_os = "windows") {
+        Command::new("cmd").args(["/C", "start", url]).spawn()
+    } else {
+        Command::new("xdg-open").arg(url).spawn()
+    };
+
+    if let Err(err) = opener {
+        ceprintln!("<yellow>Could not open browser: {err}</yellow>");
+    }
+}
+
 pub fn send_reload(tx: &Sender<Bytes>) -> Result<()> {
+    if !Config::get().live_reload {
+        return Ok(());
+    }
+
     tx.send("event: reload\ndata: \n\n".into())
         .with_context(|| "Could not send reload event")?;
     Ok(())
@@ -69,7 +100,14 @@ async fn reponse(
 ) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
     match (req.method(), req.uri().path()) {
         (&Method::GET, "/sse") => event_stream(tx).await,
-        (&Method::GET, path) => page_send(path),
+        // a page named search is still served at `/search` without a query
+        (&Method::GET, "/search") if search_query(req.uri().query()).is_some() => {
+            search_send(&search_query(req.uri().query()).unwrap_or_default())
+        }
+        (&Method::GET, path) if path.starts_with(&format!("/{SEARCH_DIR}/")) => {
+            search_file_send(path)
+        }
+        (&Method::GET, path) => page_send(path).await,
         _ => not_found(),
     }
 }
@@ -82,38 +120,118 @@ fn not_found() -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
     )?)
 }
 
-fn page_send(url: &str) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
-    let context = CONTEXT.lock().unwrap();
-    let index_url = format!("/{}", Config::get().index_name);
-    if url == index_url {
-        index_send(&context.reads)
-    } else {
-        match context.reads.get(&Locator::from_url(url)) {
-            Some(node) => {
-                let root = get_root(&context.reads)?;
-                let node = node.lock().unwrap();
+async fn page_send(url: &str) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
+    if escapes_root(url) {
+        return not_found();
+    }
 
-                let page = match node.deref() {
-                    ThreadNode::Section(section) => &section.body,
-                    ThreadNode::Page(page) => page,
-                };
+    // the context lock must be released before reading files asynchronously
+    let asset = {
+        let context = CONTEXT.lock().unwrap();
+        let index_url = format!("/{}", Config::get().index_name);
+        if url == index_url {
+            return index_send(&context.reads);
+        }
 
-                let html = page.render(&root)?;
+        if let Some(node) = context.reads.get(&Locator::from_url(url)) {
+            let root = get_root(&context.reads)?;
+            let node = node.lock().unwrap();
 
-                Ok(Response::builder()
-                    .status(StatusCode::OK)
-                    .body(Full::new(html.into()).map_err(|e| match e {}).boxed())?)
-            }
-            None => static_file_serve(url),
+            let page = match node.deref() {
+                ThreadNode::Section(section) => &section.body,
+                ThreadNode::Page(page) => page,
+            };
+
+            let html = match page.render(&root) {
+                Ok(html) => html,
+                Err(err) => return render_error(&err),
+            };
+
+            return Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "text/html; charset=utf-8")
+                .body(Full::new(html.into()).map_err(|e| match e {}).boxed())?);
         }
+
+        context.assets.get(&Locator::from_url(url)).cloned()
+    };
+
+    match asset {
+        Some(path) => file_serve(&path).await,
+        None => static_file_serve(url).await,
     }
 }
 
-fn index_send(
-    reads: &HashMap<Locator, ThreadNodeType>,
-) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
-    let index = render_index(reads)?;
-    let json = serde_json::to_string(&index)?;
+/// urls with `..` components could read any file on disk through the static dir or an asset
+fn escapes_root(url: &str) -> bool {
+    url.split(['/', '\\']).any(|part| part == "..")
+}
+
+/// shows why a page could not be rendered, e.g. a mistake in a template
+fn render_error(err: &anyhow::Error) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
+    ceprintln!("<red>{err:#}</red>");
+
+    Ok(Response::builder()
+        .status(StatusCode::INTERNAL_SERVER_ERROR)
+        .header("Content-Type", "text/plain; charset=utf-8")
+        .body(
+            Full::new(format!("{err:#}").into())
+                .map_err(|e| match e {})
+                .boxed(),
+        )?)
+}
+
+/// the `q` parameter of a query string
+fn search_query(query: Option<&str>) -> Option<String> {
+    form_urlencoded::parse(query?.as_bytes())
+        .find(|(key, _)| key == "q")
+        .map(|(_, value)| value.into_owned())
+}
+
+/// answers `/search?q=...` with the best matching parts of pages as JSON
+fn search_send(q: &str) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
+    let mut context = CONTEXT.lock().unwrap();
+    let hits = search_index(&mut context)?.search(q);
+
+    json_send(&serde_json::json!({ "query": q, "hits": hits }))
+}
+
+/// answers with the files of the static search index, as `build` would write them
+fn search_file_send(path: &str) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
+    let file = path
+        .strip_prefix(&format!("/{SEARCH_DIR}/"))
+        .unwrap_or_default();
+
+    let mut context = CONTEXT.lock().unwrap();
+    let index = search_index(&mut context)?;
+    if file == "docs.json" {
+        return json_send(&index.manifest());
+    }
+
+    let Some((dir, name)) = file
+        .strip_suffix(".json")
+        .and_then(|file| file.split_once('/'))
+    else {
+        return not_found();
+    };
+    match (dir, name.parse::<usize>()) {
+        ("terms", _) => json_send(&index.shard(name)),
+        ("texts", Ok(batch)) => json_send(&index.texts(batch)),
+        _ => not_found(),
+    }
+}
+
+/// the search index of the current content, built when it is first needed
+fn search_index(context: &mut Static) -> Result<&SearchIndex> {
+    let index = match context.search.take() {
+        Some(index) => index,
+        None => SearchIndex::new(render_index(&context.reads)?),
+    };
+    Ok(context.search.insert(index))
+}
+
+fn json_send(value: &impl Serialize) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
+    let json = serde_json::to_string(value)?;
     let body = Full::new(json.into()).map_err(|e| match e {}).boxed();
 
     let response = Response::builder()
@@ -123,6 +241,12 @@ fn index_send(
     Ok(response)
 }
 
+fn index_send(
+    reads: &HashMap<Locator, ThreadNodeType>,
+) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
+    json_send(&render_index(reads)?)
+}
+
 async fn event_stream(tx: Sender<Bytes>) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
     let rx2 = tx.subscribe();
     let stream = BroadcastStream::from(rx2);
@@ -142,16 +266,39 @@ async fn event_stream(tx: Sender<Bytes>) -> Result<Response<BoxBody<Bytes, anyho
     Ok(response)
 }
 
-fn static_file_serve(url: &str) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
+async fn static_file_serve(url: &str) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
     let loc = Locator::from_url(url);
-    let file = File::open(loc.static_path());
-    if let Ok(mut file) = file {
-        let mut content = String::new();
-        file.read_to_string(&mut content)?;
-        Ok(Response::builder()
-            .status(StatusCode::OK)
-            .body(Full::new(content.into()).map_err(|e| match e {}).boxed())?)
-    } else {
-        not_found()
+    file_serve(&loc.static_path()).await
+}
+
+/// streams a file with a content type based on its extension
+async fn file_serve(path: &Path) -> Result<Response<BoxBody<Bytes, anyhow::Error>>> {
+    let Ok(file) = File::open(path).await else {
+        return not_found();
+    };
+    // directories can be opened as well, but not read
+    if !file
+        .metadata()
+        .await
+        .is_ok_and(|metadata| metadata.is_file())
+    {
+        return not_found();
     }
+
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    let content_type = if mime.type_() == mime::TEXT {
+        format!("{mime}; charset=utf-8")
+    } else {
+        mime.to_string()
+    };
+
+    let display_path = path.display().to_string();
+    let reader_stream = ReaderStream::new(file)
+        .map_ok(hyper::body::Frame::data)
+        .map_err(move |err| anyhow!("Failed to read file '{display_path}': {err:}"));
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", content_type)
+        .body(StreamBody::new(reader_stream).boxed())?)
 }
@@ -1,15 +1,156 @@
-use askama::Template;
+use std::{
+    error::Error,
+    fs::read_to_string,
+    path::PathBuf,
+    sync::{LazyLock, RwLock},
+};
 
-#[derive(Template)]
-#[template(path = "content_table.html")]
+use anyhow::{Context, Result, anyhow};
+use include_dir::{Dir, include_dir};
+use minijinja::{Environment, ErrorKind};
+use serde::Serialize;
+
+use crate::{
+    config::{Config, SiteConfig},
+    reader::Page,
+};
+
+pub static TEMPLATE_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/");
+
+static TEMPLATES: LazyLock<RwLock<Environment<'static>>> =
+    LazyLock::new(|| RwLock::new(environment()));
+
+/// templates are loaded when first used, each from the project's templates directory if it is
+/// there and otherwise from the ones built into the binary
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_loader(|name| match project_template(name) {
+        Some(path) => read_to_string(&path).map(Some).map_err(|err| {
+            minijinja::Error::new(
+                ErrorKind::InvalidOperation,
+                format!("could not read template '{}'", path.display()),
+            )
+            .with_source(err)
+        }),
+        None => Ok(TEMPLATE_DIR
+            .get_file(name)
+            .and_then(|file| file.contents_utf8())
+            .map(String::from)),
+    });
+    env
+}
+
+/// the file in the project's templates directory that replaces a built-in template, if any
+fn project_template(name: &str) -> Option<PathBuf> {
+    // names come from templates, which should not be able to read files outside the directory
+    if name.split('/').any(|part| part == "..") {
+        return None;
+    }
+
+    let path = Config::get().templates.join(name);
+    path.is_file().then_some(path)
+}
+
+/// forget all loaded templates, so they are read again the next time they are used
+pub fn reload_templates() {
+    *TEMPLATES.write().unwrap() = environment();
+}
+
+fn render(name: &str, context: impl Serialize) -> Result<String> {
+    let env = TEMPLATES.read().unwrap();
+    env.get_template(name)
+        .and_then(|template| template.render(context))
+        .map_err(template_error)
+        .with_context(|| format!("Failed to render template '{name}'"))
+}
+
+/// whether there is a template for the layout, in the project or built in
+pub fn layout_exists(layout: &str) -> bool {
+    let env = TEMPLATES.read().unwrap();
+    // a template that fails to compile exists, its error is reported when rendering
+    !matches!(
+        env.get_template(&format!("layouts/{layout}.html")),
+        Err(err) if err.kind() == ErrorKind::TemplateNotFound
+    )
+}
+
+/// describes a template error with the file and line it happened on
+fn template_error(err: minijinja::Error) -> anyhow::Error {
+    // errors in included templates are wrapped, the innermost one tells where it went wrong
+    let mut inner = &err;
+    while let Some(source) = inner
+        .source()
+        .and_then(|source| source.downcast_ref::<minijinja::Error>())
+    {
+        inner = source;
+    }
+
+    let Some(name) = inner.name() else {
+        return err.into();
+    };
+
+    let file = match project_template(name) {
+        Some(path) => path.display().to_string(),
+        None => format!("{name} (built-in)"),
+    };
+    let location = match inner.line() {
+        Some(line) => format!("{file}:{line}"),
+        None => file,
+    };
+    let detail = match inner.detail() {
+        Some(detail) => format!("{}: {detail}", inner.kind()),
+        None => inner.kind().to_string(),
+    };
+
+    anyhow!("{location}: {detail}")
+}
+
+/// a page in the navigation tree, rendered recursively with its children
+#[derive(Serialize)]
+pub struct NavNode<'a> {
+    pub page: &'a Page,
+    pub depth: usize,
+    /// the current page is somewhere below this node
+    pub is_ancestor: bool,
+    pub is_current: bool,
+    pub children: Vec<NavNode<'a>>,
+}
+
+#[derive(Serialize)]
 pub struct ContentTableTemplate<'a> {
-    pub pages: Vec<&'a crate::reader::Page>,
-    pub curr_page: &'a crate::reader::Page,
+    pub site: &'a SiteConfig,
+    pub root: NavNode<'a>,
+}
+
+impl ContentTableTemplate<'_> {
+    pub fn render(&self) -> Result<String> {
+        render("content_table.html", self)
+    }
+}
+
+/// the context of a layout, every layout extends `base.html`
+#[derive(Serialize)]
+pub struct LayoutTemplate<'a> {
+    pub site: &'a SiteConfig,
+    /// name of the layout, without the `layouts/` directory and extension
+    pub layout: String,
+    /// the rendered navigation tree
+    pub table: String,
+    pub page: &'a Page,
+    /// index pages of the subsections and the pages in the section, empty for normal pages
+    pub children: Vec<&'a Page>,
+    /// the path from the root to the current page, including the page itself
+    pub breadcrumbs: Vec<&'a Page>,
+    /// previous page in reading order
+    pub prev: Option<&'a Page>,
+    /// next page in reading order
+    pub next: Option<&'a Page>,
+    /// include the script that reloads the page when the server sends a reload event
+    pub live_reload: bool,
 }
 
-#[derive(Template)]
-#[template(path = "base.html", escape = "none")]
-pub struct BaseTemplate<'a> {
-    pub table: &'a ContentTableTemplate<'a>,
-    pub page: &'a crate::reader::Page,
+impl LayoutTemplate<'_> {
+    pub fn render(&self) -> Result<String> {
+        render(&format!("layouts/{}.html", self.layout), self)
+    }
 }
@@ -1,30 +1,27 @@
 use std::{
-    ffi::OsStr,
     fs::read_to_string,
     ops::{Deref, DerefMut},
-    path::Path,
+    path::{Path, PathBuf},
     thread::{self, JoinHandle},
     time::{Duration, SystemTime},
 };
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use color_print::ceprintln;
 use hyper::body::Bytes;
 use tokio::sync::broadcast::Sender;
 
 use crate::{
-    CONTEXT,
+    CONTEXT, Static,
     config::Config,
     link_checker::check_links,
     locator::Locator,
-    reader::{ThreadNode, markdown_to_html, read},
+    reader::{ThreadNode, file_title, is_asset, is_draft_path, parse_page, read, read_page},
     serve::send_reload,
+    templates::reload_templates,
 };
 
-use notify::{
-    Event, EventKind, RecursiveMode,
-    event::{CreateKind, ModifyKind, RemoveKind},
-};
+use notify::{Event, EventKind, RecursiveMode, event::ModifyKind};
 use notify_debouncer_full::{DebouncedEvent, new_debouncer};
 
 pub fn spawn_watcher_thread(sse: Sender<Bytes>) -> JoinHandle<()> {
@@ -34,8 +31,13 @@ pub fn spawn_watcher_thread(sse: Sender<Bytes>) -> JoinHandle<()> {
         let mut debouncer =
             new_debouncer(Duration::from_millis(20), None, tx).expect("Could not create debouncer");
 
+        // an absolute path makes the events contain absolute paths, also for removed files
+        let content = Config::get()
+            .content
+            .canonicalize()
+            .expect("Could not find content directory");
         debouncer
-            .watch(Path::new(&Config::get().content), RecursiveMode::Recursive)
+            .watch(&content, RecursiveMode::Recursive)
             .expect("Could not watch directory");
 
         ceprintln!(
@@ -43,12 +45,23 @@ pub fn spawn_watcher_thread(sse: Sender<Bytes>) -> JoinHandle<()> {
             Config::get().content.to_string_lossy()
         );
 
+        // these are optional, without them the built-in static files and templates are used
+        for dir in [&Config::get().static_dir, &Config::get().templates] {
+            if let Ok(dir) = dir.canonicalize() {
+                debouncer
+                    .watch(&dir, RecursiveMode::Recursive)
+                    .expect("Could not watch directory");
+                ceprintln!("<blue>Watching {}</blue>", dir.to_string_lossy());
+            }
+        }
+
         for result in rx {
             match result {
                 Ok(events) => events.iter().for_each(|e| {
                     let now = SystemTime::now();
                     match handle_event(e) {
                         Ok(true) => {
+                            CONTEXT.lock().unwrap().search = None;
                             if let Err(err) = send_reload(&sse) {
                                 ceprintln!("<red>{err}</red>");
                             }
@@ -69,6 +82,30 @@ pub fn spawn_watcher_thread(sse: Sender<Bytes>) -> JoinHandle<()> {
 }
 
 fn handle_event(event: &DebouncedEvent) -> Result<bool> {
+    if !matches!(event.event.kind, EventKind::Access(_)) {
+        // static files are read from disk when requested, so reloading the browser is enough
+        if event
+            .event
+            .paths
+            .iter()
+            .any(|path| is_in_dir(path, &Config::get().static_dir))
+        {
+            println!("Detected change in static files");
+            return Ok(true);
+        }
+
+        if event
+            .event
+            .paths
+            .iter()
+            .any(|path| is_in_dir(path, &Config::get().templates))
+        {
+            reload_templates();
+            println!("Detected change in templates");
+            return Ok(true);
+        }
+    }
+
     let updated = match event {
         DebouncedEvent {
             event:
@@ -79,102 +116,82 @@ fn handle_event(event: &DebouncedEvent) -> Result<bool> {
                 },
             ..
         } => {
-            let context = CONTEXT.lock().unwrap();
+            let mut context = CONTEXT.lock().unwrap();
+            // sections that became or stopped being a draft, their pages inherit it
+            let mut changed_drafts = Vec::new();
             for path in paths {
+                // assets are read from disk when requested
+                if is_asset(path) {
+                    println!("Detected change for asset: {}", path.display());
+                    continue;
+                }
+
                 let loc = Locator::from_content_path(path)?;
+                let parent = match path.ends_with("index.md") {
+                    true => loc.parent().parent(),
+                    false => loc.parent(),
+                };
+                let parent_draft = parent != loc && is_draft_section(&parent, &context);
+
                 if let Some(node) = context.reads.get(&loc) {
-                    let text = read_to_string(path)?;
                     match node.lock().unwrap().deref_mut() {
                         ThreadNode::Section(section) => {
-                            let (page_body, links) = markdown_to_html(text, &section.body.loc)?;
-                            section.body.content = page_body;
-                            section.body.links = links;
-                            check_links(&section.body, &context.reads);
+                            let section_dir = path.parent().with_context(|| {
+                                format!("Could not get section of index.md: {}", path.display())
+                            })?;
+                            let mut body = parse_page(
+                                &read_to_string(path)?,
+                                file_title(section_dir)?,
+                                section.body.loc.clone(),
+                            )?;
+                            body.draft |= parent_draft || is_draft_path(section_dir);
+                            if body.draft != section.body.draft {
+                                changed_drafts.push(section_dir.to_path_buf());
+                            }
+
+                            section.body = body;
+                            check_links(&section.body, &context);
                         }
                         ThreadNode::Page(page) => {
-                            let (page_body, links) = markdown_to_html(text, &page.loc)?;
-                            page.content = page_body;
-                            page.links = links;
-                            check_links(page, &context.reads);
+                            *page = read_page(path, &page.loc.parent(), parent_draft)?;
+                            check_links(page, &context);
                         }
                     }
 
                     println!("Detected change for url: {}", loc.url());
                 }
             }
-            true
-        }
-        DebouncedEvent {
-            event:
-                Event {
-                    kind: EventKind::Create(CreateKind::File),
-                    paths,
-                    ..
-                },
-            ..
-        } => {
-            let mut context = CONTEXT.lock().unwrap();
-            for path in paths {
-                let path = path.canonicalize()?;
-
-                let parent_locator = Locator::from_content_path(&path)?.parent();
 
-                let mut parent = path.parent().with_context(|| {
-                    format!("Could not get parent of created file: {}", path.display())
-                })?;
-
-                if path.file_stem() == Some(OsStr::new("index")) {
-                    parent = parent.parent().with_context(|| {
-                        format!(
-                            "Could not get parent of parent of index.md: {}",
-                            path.display()
-                        )
-                    })?;
-                }
-
-                let new_node = read(parent, &parent_locator.parent(), &mut context)?;
-                if let Some(parent_node) = context.reads.get(&parent_locator) {
-                    let mut parent_node = parent_node.lock().unwrap();
-                    let parent_section = parent_node
-                        .get_section_mut()
-                        .context("Impossible situation encountered on file create event!")?;
-                    *parent_section = new_node;
-                    println!("Detected added page");
-                }
+            for dir in changed_drafts {
+                reload_section(&dir, &mut context)?;
             }
             true
         }
-
         DebouncedEvent {
             event:
                 Event {
-                    kind: EventKind::Remove(RemoveKind::File | RemoveKind::Folder),
+                    kind:
+                        EventKind::Create(_)
+                        | EventKind::Remove(_)
+                        | EventKind::Modify(ModifyKind::Name(_)),
                     paths,
                     ..
                 },
             ..
         } => {
             let mut context = CONTEXT.lock().unwrap();
-            for path in paths {
-                let loc = Locator::from_content_path(path)?;
 
-                context.reads.remove(&loc);
-
-                let parent_locator = loc.parent();
-                if let Some(parent_node) = context.reads.get(&parent_locator) {
-                    let mut parent_node = parent_node.lock().unwrap();
-                    let parent_section = parent_node
-                        .get_section_mut()
-                        .context("Impossible situation encountered on file delete event!")?;
-
-                    parent_section
-                        .children
-                        .retain(|child| match child.lock().unwrap().deref() {
-                            ThreadNode::Section(section) => section.body.loc != loc,
-                            ThreadNode::Page(page) => page.loc != loc,
-                        });
-                    println!("Detected file removal");
-                };
+            // a rename has both the old and new path, each changes the section it is in
+            let mut sections: Vec<PathBuf> = paths
+                .iter()
+                .filter_map(|path| path.parent().map(Path::to_path_buf))
+                .collect();
+            sections.sort();
+            sections.dedup();
+
+            for section in sections {
+                let loc = reload_section(&section, &mut context)?;
+                println!("Detected structure change in section: {}", loc.url());
             }
             true
         }
@@ -183,3 +200,64 @@ fn handle_event(event: &DebouncedEvent) -> Result<bool> {
 
     Ok(updated)
 }
+
+/// reads a section directory again, replacing the section and everything below it
+///
+/// directories that are not known yet, because they are new or were moved, are read through
+/// their closest known parent. Returns the locator of the section that was read.
+fn reload_section(dir: &Path, context: &mut Static) -> Result<Locator> {
+    let content = Config::get().content.canonicalize()?;
+    if !dir.exists() || Locator::from_content_path(dir).is_err() {
+        return reload_parent(dir, &content, context);
+    }
+
+    let loc = Locator::from_content_path(dir)?;
+    let Some(node) = context.reads.get(&loc).cloned() else {
+        return reload_parent(dir, &content, context);
+    };
+
+    // read into a new context, so a failed read keeps the old state intact
+    let mut fresh = Static::default();
+    let parent_draft = loc.parent() != loc && is_draft_section(&loc.parent(), context);
+    let section = read(dir, &loc.parent(), parent_draft, &mut fresh)?;
+
+    context
+        .reads
+        .retain(|key, _| key == &loc || !key.starts_with(&loc));
+    context.assets.retain(|key, _| !key.starts_with(&loc));
+    context.reads.extend(fresh.reads);
+    context.assets.extend(fresh.assets);
+    *node
+        .lock()
+        .unwrap()
+        .get_section_mut()
+        .context("Impossible situation encountered on section reload!")? = section;
+
+    Ok(loc)
+}
+
+fn reload_parent(dir: &Path, content: &Path, context: &mut Static) -> Result<Locator> {
+    if dir == content || !dir.starts_with(content) {
+        bail!("Could not find a section to reload for {}", dir.display());
+    }
+
+    let parent = dir
+        .parent()
+        .with_context(|| format!("Could not get parent of directory: {}", dir.display()))?;
+    reload_section(parent, context)
+}
+
+fn is_in_dir(path: &Path, dir: &Path) -> bool {
+    dir.canonicalize().is_ok_and(|dir| path.starts_with(dir))
+}
+
+/// whether the section with the given locator is a draft, which makes everything in it one
+fn is_draft_section(loc: &Locator, context: &Static) -> bool {
+    context
+        .reads
+        .get(loc)
+        .is_some_and(|node| match node.lock().unwrap().deref() {
+            ThreadNode::Section(section) => section.body.draft,
+            ThreadNode::Page(_) => false,
+        })
+}
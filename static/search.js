@@ -1,25 +1,35 @@
-fuse = null;
+const MAX_HITS = 20;
+
+let manifest = null;
+// promises of the shards that were requested, by key
+const shards = new Map();
+// promises of the batches of texts that were requested, by number
+const texts = new Map();
 
 const search_input = document.querySelector(".search-input");
 const search_list = document.querySelector(".search-list");
 const search_results = document.querySelector(".search-results");
 
 search_input.addEventListener("keyup", async function(event) {
-  if (fuse === null) {
-    fuse = await initSearch();
+  if (manifest === null) {
+    manifest = await fetchJson("/_search/docs.json");
     console.log("Initializing Search");
   }
 
-  search_list.innerHTML = "";
+  const words = tokenize(event.target.value);
+  const hits = await search(words);
 
-  const items = fuse.search(event.target.value);
-  items.forEach((match) => {
+  const shown = hits.slice(0, MAX_HITS);
+  const shown_texts = await Promise.all(shown.map((hit) => docText(hit.doc)));
+
+  search_list.innerHTML = "";
+  shown.forEach((hit, i) => {
     let element = document.createElement("li");
-    element.innerHTML = searchItem(match);
+    element.innerHTML = searchItem(manifest.docs[hit.doc], shown_texts[i], words);
     search_list.appendChild(element);
   })
 
-  if (items.length > 0) {
+  if (hits.length > 0) {
     search_results.style.display = "block";
   } else {
     search_results.style.display = "none";
@@ -28,69 +38,121 @@ search_input.addEventListener("keyup", async function(event) {
 });
 
 
-async function initSearch() {
-  return await fetch("/index.json").then(async function(response) {
-    if (!response.ok) {
-          throw new Error(`Response status: ${response.status}`);
-    }
-    const options = {
-      keys: [
-        "title",
-        "html"
-      ],
-      includeMatches: true,
-      minMatchCharLength: 3,
-      /* findAllMatches: true */
-      ignoreLocation: true
-    };
-    return new Fuse(await response.json(), options);
-  });
+async function fetchJson(url) {
+  const response = await fetch(url);
+  if (!response.ok) {
+    throw new Error(`Response status: ${response.status}`);
+  }
+  return await response.json();
+}
+
+function tokenize(text) {
+  return text.toLowerCase().match(/[\p{Alphabetic}\p{N}]+/gu) ?? [];
 }
 
-function makeTeaser(match) {
-  const [start, stop, largest] = largestMatch(match);
-  if (largest.key == "title") {
-    return [`<h1> ${largest.value} </h1>`, largest.value, ""];
-  } else if (largest.key == "html"){
-    const exact_match = largest.value.slice(start, stop + 1);
-    const suffix = largest.value.slice(stop + 1).split(" ")[0].replace(/<[^>]*>?/gm, '');
-    let teaser = largest.value.slice(Math.max(0, start - 60),start) 
-      + '<b style="text-decoration: underline;">' 
-      + exact_match
-      + '</b>' 
-      + largest.value.slice(stop + 1, Math.min(largest.value.length, stop + 61));
-    return [teaser, exact_match, suffix];
+/* the same key the index was split by when it was built */
+function shardKey(term) {
+  return [...term]
+    .slice(0, manifest.prefix_length)
+    .map((c) => /^[a-z0-9]$/.test(c) ? c : "_" + c.codePointAt(0).toString(16))
+    .join("");
+}
+
+function loadShard(key) {
+  if (!shards.has(key)) {
+    shards.set(key, fetchJson(`/_search/terms/${key}.json`));
   }
+  return shards.get(key);
 }
 
-function largestMatch(item) {
-  let max_length = 0;
-  let start;
-  let stop;
-  let key_match;
-
-  item.matches.forEach((key) => {
-    for (let i = 0; i < key.indices.length; i++) {
-      const length = key.indices[i][1] - key.indices[i][0] + 1;
-      if (length > max_length) {
-        max_length = length;
-        start = key.indices[i][0];
-        stop = key.indices[i][1];
-        key_match = key;
+/* the text of a document, only needed for the teaser of a result */
+async function docText(doc) {
+  const batch = Math.floor(doc / manifest.text_batch_size);
+  if (!texts.has(batch)) {
+    texts.set(batch, fetchJson(`/_search/texts/${batch}.json`));
+  }
+  return (await texts.get(batch))[doc % manifest.text_batch_size];
+}
+
+/* the postings of every term matching the word, the last word of a query also matches longer words */
+async function postings(word, prefix) {
+  const key = shardKey(word);
+  const keys = manifest.shards.filter((shard) => prefix ? shard.startsWith(key) : shard == key);
+  const loaded = await Promise.all(keys.map(loadShard));
+
+  return loaded.flatMap((shard) => Object.entries(shard)
+    .filter(([term]) => prefix ? term.startsWith(word) : term == word)
+    .map(([, postings]) => postings));
+}
+
+/* documents containing every word, ranked by the frequency of the words weighted by their rarity
+   and the weight of the page */
+async function search(words) {
+  const scores = new Map();
+
+  for (let i = 0; i < words.length; i++) {
+    const word_scores = new Map();
+    for (const term_postings of await postings(words[i], i == words.length - 1)) {
+      const idf = Math.log(1 + manifest.docs.length / term_postings.length);
+      for (const [doc, frequency] of term_postings) {
+        word_scores.set(doc, Math.max(word_scores.get(doc) ?? 0, frequency * idf));
       }
     }
-  });
 
-  return [start, stop, key_match]
+    for (const [doc, score] of word_scores) {
+      const [matched, total] = scores.get(doc) ?? [0, 0];
+      scores.set(doc, [matched + 1, total + score]);
+    }
+  }
+
+  return [...scores]
+    .filter(([, [matched]]) => matched == words.length)
+    .map(([doc, [, score]]) => ({ doc, score: score * (manifest.docs[doc].weight ?? 1) }))
+    .sort((a, b) => b.score - a.score || a.doc - b.doc);
+}
+
+/* the text around the first word matching the query, with the match underlined */
+function makeTeaser(text, words) {
+  const regex = /[\p{Alphabetic}\p{N}]+/gu;
+  let match;
+  while ((match = regex.exec(text)) !== null) {
+    const token = match[0].toLowerCase();
+    if (words.some((word) => token.startsWith(word))) {
+      break;
+    }
+  }
+
+  if (match === null) {
+    return [escapeHtml(text.slice(0, 120)), ""];
+  }
+
+  const start = match.index;
+  const stop = start + match[0].length;
+  const teaser = escapeHtml(text.slice(Math.max(0, start - 60), start))
+    + '<b style="text-decoration: underline;">'
+    + escapeHtml(match[0])
+    + '</b>'
+    + escapeHtml(text.slice(stop, Math.min(text.length, stop + 60)));
+  return [teaser, match[0]];
+}
+
+function escapeHtml(text) {
+  const element = document.createElement("div");
+  element.textContent = text;
+  return element.innerHTML;
 }
 
 
-function searchItem(match) {
-  const [teaser, text, suffix] = makeTeaser(match);
-  const suffix_part = suffix.length ? `,-${suffix}` : "";
-  const url = encodeURI(`${match.item.url}#:~:text=${text}${suffix_part}`)
+function searchItem(doc, doc_text, words) {
+  const [teaser, text] = makeTeaser(doc_text, words);
+  const anchor = doc.anchor ?? "";
+  const text_part = text.length ? `:~:text=${text}` : "";
+  const fragment = anchor + text_part;
+  const url = encodeURI(fragment.length ? `${doc.url}#${fragment}` : doc.url);
+  const path = [...doc.breadcrumb, doc.title].map(escapeHtml).join(" / ");
+  const heading = doc.heading ? ` &rsaquo; ${escapeHtml(doc.heading)}` : "";
   return `<div class="search-item">`
-    + `<a href="${url}">${match.item.title}</a>`
+    + `<a href="${url}">${path}${heading}</a>`
   + `<div>${teaser}</div>`
   + `</div>`
 